@@ -0,0 +1,382 @@
+//! Derive macro for the [`ropts`](https://github.com/StevenCyb/ropts) command line argument parser.
+//!
+//! Use it through the `derive` feature of `ropts` instead of depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, ExprLit, Field, Fields, GenericArgument, Ident,
+    Lit, LitChar, LitStr, PathArguments, Type, Variant,
+};
+
+/// Implements `ropts::derive::Ropts` for a struct with named fields.
+///
+/// Every field becomes an option with the field name as id and its doc comment as description.
+/// The field type selects the option:
+/// - `Option<Vec<T>>` and `Vec<T>` become a `ValuesOption<T>`,
+/// - `Option<T>` becomes an optional `ValueOption<T>`,
+/// - `bool` becomes a switch that is `false` if not set,
+/// - any other `T` becomes a `ValueOption<T>` that is required unless it has a default.
+///
+/// Field attributes:
+/// - `#[ropts(env = "KEY")]` reads the value from the environment variable `KEY`,
+/// - `#[ropts(short)]` or `#[ropts(short = 'n')]` adds a short argument, defaulting to the first letter of the field name,
+/// - `#[ropts(long)]` or `#[ropts(long = "name")]` adds a long argument, defaulting to the field name in kebab case,
+/// - `#[ropts(required)]` marks the option as required,
+/// - `#[ropts(default = value)]` sets the default value,
+/// - `#[ropts(group = "Section")]` lists the option under `Section` in the help,
+/// - `#[ropts(value_name = "NAME")]` sets the placeholder of the value in the help.
+///
+/// A field without `env`, `short` or `long` gets a long argument.
+#[proc_macro_derive(Ropts, attributes(ropts))]
+pub fn derive_ropts(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `ropts::options::ValueEnum` and `FromStr` for an enum with unit variants,
+/// so it can be used in a `ValueOption` or `ValuesOption`.
+///
+/// The variants are named in kebab case on the command line, e.g. `JsonLines` as `json-lines`,
+/// and matched ignoring the case.
+///
+/// Variant attributes:
+/// - `#[ropts(name = "name")]` sets the name of the variant.
+#[proc_macro_derive(ValueEnum, attributes(ropts))]
+pub fn derive_value_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_value_enum(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Kind {
+    /// `T`, required unless a default is set.
+    Value,
+    /// `Option<T>`.
+    OptionalValue,
+    /// `bool`, `false` if not set.
+    Switch,
+    /// `Vec<T>`, empty if not set.
+    Values,
+    /// `Option<Vec<T>>`.
+    OptionalValues,
+}
+
+#[derive(Default)]
+struct FieldAttributes {
+    env: Option<LitStr>,
+    short: Option<Option<LitChar>>,
+    long: Option<Option<LitStr>>,
+    required: bool,
+    default: Option<Expr>,
+    group: Option<LitStr>,
+    value_name: Option<LitStr>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Ropts can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Ropts can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut registrations = Vec::new();
+    let mut initializers = Vec::new();
+    for field in fields {
+        let (registration, initializer) = expand_field(field)?;
+        registrations.push(registration);
+        initializers.push(initializer);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ropts::derive::Ropts for #name #ty_generics #where_clause {
+            fn register(compose: ::ropts::compose::Compose<'_>) -> ::ropts::compose::Compose<'_> {
+                compose #(#registrations)*
+            }
+
+            fn from_matches(
+                matches: &::ropts::matches::Matches,
+            ) -> ::core::result::Result<Self, ::ropts::error::Error> {
+                ::core::result::Result::Ok(Self { #(#initializers,)* })
+            }
+        }
+    })
+}
+
+fn expand_value_enum(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ValueEnum can only be derived for enums",
+        ));
+    };
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ValueEnum can not be derived for enums without variants",
+        ));
+    }
+
+    let mut variants = Vec::new();
+    let mut names = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "ValueEnum can only be derived for enums with unit variants",
+            ));
+        }
+        let ident = &variant.ident;
+        variants.push(quote!(Self::#ident));
+        names.push(variant_name(variant)?);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ropts::options::ValueEnum for #name #ty_generics #where_clause {
+            fn variants() -> &'static [Self] {
+                &[#(#variants),*]
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#variants => #names,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::ropts::error::Error;
+
+            fn from_str(name: &str) -> ::core::result::Result<Self, Self::Err> {
+                <Self as ::ropts::options::ValueEnum>::from_name(name)
+            }
+        }
+    })
+}
+
+/// Returns the name of a variant from `#[ropts(name = "...")]`,
+/// or the variant in kebab case, e.g. `json-lines` for `JsonLines`.
+fn variant_name(variant: &Variant) -> syn::Result<LitStr> {
+    let mut name = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ropts"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown ropts attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(name.unwrap_or_else(|| {
+        let mut kebab = String::new();
+        let mut previous_lowercase = false;
+        for c in variant.ident.to_string().chars() {
+            if c.is_uppercase() && previous_lowercase {
+                kebab.push('-');
+            }
+            previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+            kebab.extend(c.to_lowercase());
+        }
+        LitStr::new(&kebab, variant.ident.span())
+    }))
+}
+
+fn expand_field(field: &Field) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let ident = field.ident.as_ref().expect("named field");
+    let id = ident.to_string();
+    let description = doc_comment(field);
+    let attributes = field_attributes(field)?;
+    let (kind, inner) = classify(&field.ty);
+
+    let mut setters = Vec::new();
+    if let Some(env) = &attributes.env {
+        setters.push(quote!(.env(#env)));
+    }
+    if let Some(short) = &attributes.short {
+        let short = match short {
+            Some(short) => short.clone(),
+            None => LitChar::new(first_char(ident)?, ident.span()),
+        };
+        setters.push(quote!(.short_arg(#short)));
+    }
+    let long = match &attributes.long {
+        Some(long) => Some(long.clone()),
+        None if attributes.env.is_none() && attributes.short.is_none() => Some(None),
+        None => None,
+    };
+    if let Some(long) = long {
+        let long = long.unwrap_or_else(|| LitStr::new(&id.replace('_', "-"), ident.span()));
+        setters.push(quote!(.long_arg(#long)));
+    }
+    if let Some(value_name) = &attributes.value_name {
+        setters.push(quote!(.value_name(#value_name)));
+    }
+    if let Some(group) = &attributes.group {
+        setters.push(quote!(.group(#group)));
+    }
+    if attributes.required || (matches!(kind, Kind::Value) && attributes.default.is_none()) {
+        setters.push(quote!(.required()));
+    }
+    if let Some(default) = &attributes.default {
+        let default = match default {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) if matches!(kind, Kind::Value | Kind::OptionalValue) => {
+                quote!(<#inner as ::core::convert::From<&str>>::from(#lit))
+            }
+            default => quote!(#default),
+        };
+        setters.push(quote!(.default(#default)));
+    }
+
+    let option = match kind {
+        Kind::Value | Kind::OptionalValue | Kind::Switch => {
+            quote!(::ropts::options::ValueOption::<#inner>)
+        }
+        Kind::Values | Kind::OptionalValues => quote!(::ropts::options::ValuesOption::<#inner>),
+    };
+    let registration = quote! {
+        .add(#option::with_id(#id, #description) #(#setters)*)
+    };
+
+    let value = match kind {
+        Kind::Value => quote! {
+            matches.get::<#inner>(#id).cloned().ok_or_else(|| {
+                ::ropts::error::Error::Validation(::std::format!("{} has no value", #id))
+            })?
+        },
+        Kind::OptionalValue => quote!(matches.get::<#inner>(#id).cloned()),
+        Kind::Switch => quote!(matches.get::<bool>(#id).copied().unwrap_or_default()),
+        Kind::Values => quote! {
+            matches.get_many::<#inner>(#id).map(<[#inner]>::to_vec).unwrap_or_default()
+        },
+        Kind::OptionalValues => quote!(matches.get_many::<#inner>(#id).map(<[#inner]>::to_vec)),
+    };
+
+    Ok((registration, quote!(#ident: #value)))
+}
+
+fn field_attributes(field: &Field) -> syn::Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ropts"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("env") {
+                attributes.env = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("short") {
+                attributes.short = Some(match meta.input.peek(syn::Token![=]) {
+                    true => Some(meta.value()?.parse()?),
+                    false => None,
+                });
+            } else if meta.path.is_ident("long") {
+                attributes.long = Some(match meta.input.peek(syn::Token![=]) {
+                    true => Some(meta.value()?.parse()?),
+                    false => None,
+                });
+            } else if meta.path.is_ident("required") {
+                attributes.required = true;
+            } else if meta.path.is_ident("value_name") {
+                attributes.value_name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("group") {
+                attributes.group = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                attributes.default = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown ropts attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(attributes)
+}
+
+fn doc_comment(field: &Field) -> String {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Some(lit.value().trim().to_string()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn first_char(ident: &Ident) -> syn::Result<char> {
+    ident
+        .to_string()
+        .chars()
+        .find(char::is_ascii_alphanumeric)
+        .ok_or_else(|| syn::Error::new_spanned(ident, "cannot derive a short argument"))
+}
+
+/// Splits the field type into the option kind and the type of a single value.
+fn classify(ty: &Type) -> (Kind, &Type) {
+    if let Some(inner) = generic_argument(ty, "Option") {
+        if let Some(inner) = generic_argument(inner, "Vec") {
+            return (Kind::OptionalValues, inner);
+        }
+        return (Kind::OptionalValue, inner);
+    }
+    if let Some(inner) = generic_argument(ty, "Vec") {
+        return (Kind::Values, inner);
+    }
+    match ty {
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool") => {
+            (Kind::Switch, ty)
+        }
+        _ => (Kind::Value, ty),
+    }
+}
+
+/// Returns `T` if `ty` is `wrapper<T>`.
+fn generic_argument<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if path.qself.is_some() || segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,118 @@
+use ropts::compose::Compose;
+use ropts::derive::Ropts;
+use ropts::error::Error;
+
+#[derive(Debug, PartialEq, Ropts)]
+struct Demo {
+    /// Your name
+    #[ropts(env = "DEMO_NAME", short = 'n', long, required)]
+    name: String,
+    /// Your age
+    #[ropts(short, long, default = 18, value_name = "YEARS")]
+    age: u8,
+    /// Your nickname
+    nick_name: Option<String>,
+    /// Your skills
+    #[ropts(short, long = "skill")]
+    skills: Option<Vec<String>>,
+    /// Lucky numbers
+    #[ropts(env = "DEMO_NUMBERS")]
+    numbers: Vec<u8>,
+    /// Print more
+    #[ropts(short)]
+    verbose: bool,
+    #[ropts(long, default = "guest", group = "Access")]
+    role: String,
+}
+
+fn compose<'a>(args: &[&str], envs: &[(&str, &str)]) -> Compose<'a> {
+    Compose::new()
+        .args(
+            args.iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+        .envs(
+            envs.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+}
+
+#[test]
+fn parse_defaults() {
+    let demo = Demo::parse(compose(&["-n", "bob"], &[])).unwrap();
+    assert_eq!(
+        demo,
+        Demo {
+            name: "bob".into(),
+            age: 18,
+            nick_name: None,
+            skills: None,
+            numbers: vec![],
+            verbose: false,
+            role: "guest".into(),
+        }
+    );
+}
+
+#[test]
+fn parse_all() {
+    let demo = Demo::parse(compose(
+        &[
+            "--age",
+            "42",
+            "--nick-name",
+            "bobby",
+            "-s",
+            "rust,go",
+            "-v",
+            "--role",
+            "admin",
+        ],
+        &[("DEMO_NAME", "bob"), ("DEMO_NUMBERS", "7,13")],
+    ))
+    .unwrap();
+    assert_eq!(
+        demo,
+        Demo {
+            name: "bob".into(),
+            age: 42,
+            nick_name: Some("bobby".into()),
+            skills: Some(vec!["rust".into(), "go".into()]),
+            numbers: vec![7, 13],
+            verbose: true,
+            role: "admin".into(),
+        }
+    );
+}
+
+#[test]
+fn parse_long_from_attribute() {
+    let demo = Demo::parse(compose(&["-n", "bob", "--skill", "rust"], &[])).unwrap();
+    assert_eq!(demo.skills, Some(vec!["rust".into()]));
+}
+
+#[test]
+fn parse_required_missing() {
+    assert_eq!(
+        Demo::parse(compose(&[], &[])).unwrap_err().to_string(),
+        Error::Validation("{DEMO_NAME, -n, --name} is required".into()).to_string()
+    );
+}
+
+#[test]
+fn help_uses_doc_comments() {
+    let help = std::rc::Rc::new(std::cell::RefCell::new(String::new()));
+    let output = help.clone();
+    let _ = Demo::parse(compose(&["--help"], &[]).help(move |text| *output.borrow_mut() = text));
+    let help = help.borrow();
+    assert!(help.contains("Your name [env: DEMO_NAME] [required]"));
+    assert!(help.contains("\nAccess:\n"));
+    assert!(help.contains("-a, --age <YEARS> "));
+    assert!(help.contains("-s, --skill <STRING>,... "));
+    assert!(help.contains("--nick-name ") && help.contains(" Your nickname\n"));
+    assert!(help.contains("$DEMO_NUMBERS ") && help.contains(" Lucky numbers\n"));
+}
@@ -0,0 +1,83 @@
+use ropts::compose::Compose;
+use ropts::derive::{Ropts, ValueEnum};
+use ropts::options::ValuesOption;
+
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+enum Format {
+    Json,
+    JsonLines,
+    #[ropts(name = "txt")]
+    Text,
+}
+
+#[derive(Debug, PartialEq, Ropts)]
+struct Demo {
+    /// Output format
+    #[ropts(short, long, default = Format::Text)]
+    format: Format,
+}
+
+fn compose<'a>(args: &[&str]) -> Compose<'a> {
+    Compose::new().args(
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter(),
+    )
+}
+
+#[test]
+fn names() {
+    assert_eq!(
+        Format::variants(),
+        &[Format::Json, Format::JsonLines, Format::Text]
+    );
+    assert_eq!(Format::JsonLines.name(), "json-lines");
+    assert_eq!(Format::Text.name(), "txt");
+    assert_eq!("JSON-Lines".parse::<Format>().unwrap(), Format::JsonLines);
+}
+
+#[test]
+fn parse_case_insensitive() {
+    let demo = Demo::parse(compose(&["--format", "JSON"])).unwrap();
+    assert_eq!(demo.format, Format::Json);
+
+    let demo = Demo::parse(compose(&[])).unwrap();
+    assert_eq!(demo.format, Format::Text);
+}
+
+#[test]
+fn parse_values() {
+    let mut formats = None::<Vec<Format>>;
+    Compose::new()
+        .args(["--formats", "txt,json"].map(String::from).into_iter())
+        .add(ValuesOption::new(&mut formats, "Formats").long_arg("formats"))
+        .parse()
+        .unwrap();
+    assert_eq!(formats, Some(vec![Format::Text, Format::Json]));
+}
+
+#[test]
+fn parse_invalid_names_choices() {
+    assert_eq!(
+        Demo::parse(compose(&["-f", "xml"]))
+            .unwrap_err()
+            .to_string(),
+        "Parsing error: {-f, --format} failed to parse argument at position 2: \
+         \"xml\" is not one of json, json-lines, txt"
+    );
+}
+
+#[test]
+fn help_lists_choices() {
+    let help = std::rc::Rc::new(std::cell::RefCell::new(String::new()));
+    let output = help.clone();
+    let _ = Demo::parse(
+        compose(&["--help"])
+            .help_width(120)
+            .help(move |text| *output.borrow_mut() = text),
+    );
+    assert!(help
+        .borrow()
+        .contains("Output format [possible values: json, json-lines, txt] [default: txt]"));
+}
@@ -1,12 +1,139 @@
-use crate::error::Error;
-use crate::options::OptionBase;
-use std::collections::HashMap;
+use crate::error::{Error, ErrorKind, Source};
+use crate::matches::Matches;
+use crate::options::{OptionBase, OptionInfo, PositionalArity};
+pub use completions::Shell;
+use completions::COMPLETE_ENV;
+use help::{option_entry, render_sections, Section};
+use man::{environment_item, escape, option_item, paragraph};
+use markdown::{options_table, subcommand_sections};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use suggestion::did_you_mean;
+use tokenizer::{tokenize, Flag, Token, TokenKind};
+
+mod completions;
+mod help;
+mod man;
+mod markdown;
+mod suggestion;
+mod tokenizer;
 
 pub struct Compose<'a> {
     options: Vec<Box<dyn OptionBase + 'a>>,
     envs: HashMap<String, String>,
     args: Vec<String>,
     print_fn: Option<Box<dyn Fn(String) + 'a>>,
+    help_long: String,
+    help_short: Option<char>,
+    version: Option<String>,
+    name: Option<String>,
+    about: Option<String>,
+    after_help: Option<String>,
+    help_width: Option<usize>,
+    allow_unknown: bool,
+    trailing: Option<&'a mut Option<Vec<String>>>,
+    subcommands: Vec<(String, Compose<'a>)>,
+    /// Names and argument positions of the subcommands leading to this one.
+    path: Vec<(usize, String)>,
+    selected_path: Option<&'a mut Option<Vec<String>>>,
+}
+
+/// The options and subcommands of a command, used to generate documents like completions.
+struct CommandInfo {
+    name: String,
+    about: Option<String>,
+    options: Vec<OptionInfo>,
+    subcommands: Vec<CommandInfo>,
+}
+
+/// Maps the registered long and short flags to the index of their option.
+struct Flags {
+    long: HashMap<String, usize>,
+    short: HashMap<char, usize>,
+}
+
+impl Flags {
+    fn new(options: &[Box<dyn OptionBase + '_>]) -> Self {
+        let mut flags = Flags {
+            long: HashMap::new(),
+            short: HashMap::new(),
+        };
+        for (index, option) in options.iter().enumerate() {
+            // Local options come before the inherited ones and take precedence.
+            if let Some(long) = option.long_arg() {
+                flags.long.entry(long.to_string()).or_insert(index);
+            }
+            if let Some(short) = option.short_arg() {
+                flags.short.entry(short).or_insert(index);
+            }
+        }
+        flags
+    }
+
+    fn find(&self, flag: Flag) -> Option<usize> {
+        match flag {
+            Flag::Long(long) => self.long.get(long).copied(),
+            Flag::Short(short) => self.short.get(&short).copied(),
+        }
+    }
+}
+
+/// Collects the errors of a parse run and the options that produced them.
+#[derive(Default)]
+struct Errors {
+    errors: Vec<Error>,
+    failed: HashSet<usize>,
+    /// The errors of the environment variables, kept until the arguments are parsed.
+    env_errors: Vec<(usize, Error)>,
+    /// The options given as argument.
+    given: HashSet<usize>,
+}
+
+impl Errors {
+    fn record(&mut self, index: usize, result: Result<(), Error>) {
+        if let Err(error) = result {
+            self.errors.push(error);
+            self.failed.insert(index);
+        }
+    }
+
+    fn record_env(&mut self, index: usize, result: Result<(), Error>) {
+        if let Err(error) = result {
+            self.env_errors.push((index, error));
+        }
+    }
+
+    fn record_arg(&mut self, index: usize, result: Result<(), Error>) {
+        self.given.insert(index);
+        self.record(index, result);
+    }
+
+    /// Reports the errors of the environment variables of options not given as argument,
+    /// an argument overrides the environment variable.
+    fn resolve_env(&mut self) {
+        let env_errors: Vec<(usize, Error)> = std::mem::take(&mut self.env_errors)
+            .into_iter()
+            .filter(|(index, _)| !self.given.contains(index))
+            .collect();
+        for (position, (index, error)) in env_errors.into_iter().enumerate() {
+            self.errors.insert(position, error);
+            self.failed.insert(index);
+        }
+    }
+
+    fn into_result(mut self) -> Result<(), Error> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(Error::Multiple(self.errors)),
+        }
+    }
+}
+
+impl Default for Compose<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Compose<'a> {
@@ -16,9 +143,22 @@ impl<'a> Compose<'a> {
             envs: HashMap::new(),
             args: Vec::new(),
             print_fn: None,
+            help_long: "help".into(),
+            help_short: Some('h'),
+            version: None,
+            name: None,
+            about: None,
+            after_help: None,
+            help_width: None,
+            allow_unknown: false,
+            trailing: None,
+            subcommands: Vec::new(),
+            path: Vec::new(),
+            selected_path: None,
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn add<T: OptionBase + 'a>(mut self, option: T) -> Self {
         self.options.push(Box::new(option));
         self
@@ -34,78 +174,802 @@ impl<'a> Compose<'a> {
         self
     }
 
-    fn envs_parse(&mut self) {
-        for option in &mut self.options {
-            option.parse_env(&self.envs);
+    /// Ignores unknown arguments instead of rejecting them,
+    /// e.g. for tools that forward arguments to another program.
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// Collects all arguments after the `--` terminator verbatim,
+    /// instead of handing them to the positional options.
+    pub fn trailing(mut self, value: &'a mut Option<Vec<String>>) -> Self {
+        self.trailing = Some(value);
+        self
+    }
+
+    /// Registers a subcommand, e.g. `db` in `tool db migrate --dry-run`.
+    /// The options of this compose are inherited by the subcommand as global options.
+    pub fn subcommand(mut self, name: &str, subcommand: Compose<'a>) -> Self {
+        self.subcommands.push((name.into(), subcommand));
+        self
+    }
+
+    /// Stores the names of the selected subcommands after parsing,
+    /// e.g. `["db", "migrate"]` for `tool db migrate --dry-run`.
+    pub fn subcommand_path(mut self, path: &'a mut Option<Vec<String>>) -> Self {
+        self.selected_path = Some(path);
+        self
+    }
+
+    fn envs_parse(&mut self, errors: &mut Errors) {
+        for (index, option) in self.options.iter_mut().enumerate() {
+            errors.record_env(index, option.parse_env(&self.envs));
         }
     }
 
-    fn args_parse(&mut self) {
-        for arg in &self.args {
-            if arg == "--help" || arg == "-h" {
-                if let Some(print_fn) = &self.print_fn {
-                    let mut help: String = "Usage: <program> [options]\n\n".to_string();
+    fn tokenize(&self, flags: &Flags) -> Vec<Token> {
+        let mut tokens = tokenize(&self.args, |flag| {
+            flags
+                .find(flag)
+                .map(|index| self.options[index].is_switch())
+        });
+        tokens.retain(|token| {
+            !self
+                .path
+                .iter()
+                .any(|(position, _)| *position == token.position)
+        });
+        tokens
+    }
 
-                    if self.options.len() > 0 {
-                        help.push_str("Options:\n");
-                        for option in &self.options {
-                            help.push_str(format!(" - {}\n", option.help()).as_str());
-                        }
+    /// Finds the index of the selected subcommand and the position of its name in the arguments.
+    /// The name follows the values of the positional options, a variadic positional option
+    /// takes all positional arguments and leaves no subcommand to select.
+    fn find_subcommand(&self) -> Option<(usize, usize)> {
+        if self.subcommands.is_empty() {
+            return None;
+        }
+
+        let mut skip = 0;
+        for arity in self.options.iter().filter_map(|option| option.positional()) {
+            match arity {
+                PositionalArity::Single => skip += 1,
+                PositionalArity::Variadic => return None,
+            }
+        }
+        let token = self
+            .tokenize(&Flags::new(&self.options))
+            .into_iter()
+            .take_while(|token| token.kind != TokenKind::Terminator)
+            .filter(|token| matches!(token.kind, TokenKind::Positional(_)))
+            .nth(skip)?;
+        let TokenKind::Positional(name) = &token.kind else {
+            return None;
+        };
+        self.subcommands
+            .iter()
+            .position(|(subcommand, _)| subcommand == name)
+            .map(|index| (index, token.position))
+    }
+
+    /// Turns the selected subcommand into a compose holding its own and all inherited options.
+    fn into_subcommand(mut self, index: usize, position: usize) -> Compose<'a> {
+        let (name, mut subcommand) = self.subcommands.remove(index);
+        // The inherited positional options take the arguments before the name and come first,
+        // the other inherited options come last so that local flags take precedence.
+        let (mut options, mut inherited): (Vec<_>, Vec<_>) = self
+            .options
+            .into_iter()
+            .partition(|option| option.positional().is_some());
+        options.append(&mut subcommand.options);
+        options.append(&mut inherited);
+        subcommand.options = options;
+        subcommand.envs = self.envs;
+        subcommand.args = self.args;
+        subcommand.print_fn = subcommand.print_fn.or(self.print_fn);
+        subcommand.help_long = self.help_long;
+        subcommand.help_short = self.help_short;
+        subcommand.version = subcommand.version.or(self.version);
+        subcommand.name = self.name;
+        subcommand.help_width = subcommand.help_width.or(self.help_width);
+        subcommand.allow_unknown |= self.allow_unknown;
+        subcommand.trailing = subcommand.trailing.or(self.trailing);
+        subcommand.path = self.path;
+        subcommand.path.push((position, name));
+        subcommand.selected_path = subcommand.selected_path.or(self.selected_path);
+        subcommand
+    }
+
+    fn args_parse(&mut self, errors: &mut Errors) -> Vec<Token> {
+        let flags = Flags::new(&self.options);
+        let tokens = self.tokenize(&flags);
+
+        let mut unconsumed = Vec::new();
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            let matched = match &token.kind {
+                TokenKind::Long(long) => flags.find(Flag::Long(long)),
+                TokenKind::Short(short) => flags.find(Flag::Short(*short)),
+                _ => None,
+            };
+            let Some(index) = matched else {
+                unconsumed.push(token);
+                continue;
+            };
+
+            let value = tokens.next_if(|next| matches!(next.kind, TokenKind::Value(_)));
+            let result = match value {
+                Some(Token {
+                    position,
+                    kind: TokenKind::Value(value),
+                }) => self.options[index].parse_arg(Some(&value), position),
+                _ => self.options[index].parse_arg(None, token.position),
+            };
+            errors.record_arg(index, result);
+        }
+
+        self.positionals_parse(unconsumed, errors)
+    }
+
+    /// Hands the unconsumed positional tokens to the positional options in declaration order.
+    fn positionals_parse(&mut self, tokens: Vec<Token>, errors: &mut Errors) -> Vec<Token> {
+        let positionals: Vec<(usize, PositionalArity)> = self
+            .options
+            .iter()
+            .enumerate()
+            .filter_map(|(index, option)| option.positional().map(|arity| (index, arity)))
+            .collect();
+
+        let mut unconsumed = Vec::new();
+        let mut positionals = positionals.into_iter().peekable();
+        let mut terminated = false;
+        for token in tokens {
+            terminated |= token.kind == TokenKind::Terminator;
+            if let (true, Some(trailing)) = (terminated, &mut self.trailing) {
+                let trailing = trailing.get_or_insert_with(Vec::new);
+                if let TokenKind::Positional(value) = &token.kind {
+                    trailing.push(value.clone());
+                }
+                continue;
+            }
+
+            match (&token.kind, positionals.peek()) {
+                (TokenKind::Positional(value), Some((index, arity))) => {
+                    errors.record_arg(
+                        *index,
+                        self.options[*index].parse_arg(Some(value), token.position),
+                    );
+                    if *arity == PositionalArity::Single {
+                        positionals.next();
                     }
-                    print_fn(help);
                 }
-                break;
+                _ => unconsumed.push(token),
             }
         }
-        for option in &mut self.options {
-            option.parse_args(&self.args);
-        }
+
+        unconsumed
     }
 
-    pub fn parse(mut self) -> Result<(), Error> {
+    /// Parses the environment variables and arguments and evaluates all options.
+    /// If the help flag is given, the help is printed and [`Error::HelpRequested`]
+    /// is returned without parsing or validating any option.
+    /// The same applies to `--version`/`-V` and [`Error::VersionRequested`] if a version is set.
+    /// All errors found are reported at once, as [`Error::Multiple`] if there is more than one.
+    pub fn parse(self) -> Result<(), Error> {
+        self.parse_matches().map(|_| ())
+    }
+
+    /// Parses like [`Compose::parse`] and returns the values of the options created with
+    /// `with_id` as [`Matches`].
+    pub fn parse_matches(mut self) -> Result<Matches, Error> {
+        if self.completion_requested() {
+            return self.complete();
+        }
+        if let Some((index, position)) = self.find_subcommand() {
+            return self.into_subcommand(index, position).parse_matches();
+        }
+        let path: Vec<String> = self.path.iter().map(|(_, name)| name.clone()).collect();
+        if let Some(selected_path) = self.selected_path.take() {
+            *selected_path = Some(path.clone());
+        }
+
+        if self.help_requested() {
+            if let Some(print_fn) = &self.print_fn {
+                print_fn(self.render_help());
+            }
+            return Err(Error::HelpRequested);
+        }
+        if let (Some(version), true) = (&self.version, self.version_requested()) {
+            if let Some(print_fn) = &self.print_fn {
+                print_fn(format!("{} {}", self.program_name(), version));
+            }
+            return Err(Error::VersionRequested);
+        }
+
+        let mut errors = Errors::default();
         if !self.envs.is_empty() {
-            self.envs_parse();
+            self.envs_parse(&mut errors);
         }
         if !self.args.is_empty() {
-            self.args_parse();
+            let unconsumed = self.args_parse(&mut errors);
+            // Only the first unknown argument is reported, the following ones are often its values.
+            let unknown = unconsumed
+                .iter()
+                .find(|token| token.kind != TokenKind::Terminator);
+            if let (false, Some(token)) = (self.allow_unknown, unknown) {
+                errors.errors.push(self.unknown_argument(token));
+            }
         }
 
-        for option in &mut self.options {
-            option.eval()?;
+        errors.resolve_env();
+
+        for (index, option) in self.options.iter_mut().enumerate() {
+            if !errors.failed.contains(&index) {
+                errors.record(index, option.eval());
+            }
         }
+        errors.into_result()?;
+
+        let values = self
+            .options
+            .iter_mut()
+            .filter_map(|option| option.take_match())
+            .collect();
+        Ok(Matches::new(values, path))
+    }
+
+    /// Parses like [`Compose::parse_matches`] but ends the process instead of returning an error.
+    /// Exits with code 0 after the help or version was printed and with [`Error::exit`] on errors.
+    pub fn parse_or_exit(self) -> Matches {
+        self.parse_matches().unwrap_or_else(|error| error.exit())
+    }
+
+    /// Checks the environment variables set with [`Compose::envs`],
+    /// or the process environment if none are set.
+    fn completion_requested(&self) -> bool {
+        match self.envs.is_empty() {
+            true => std::env::var_os(COMPLETE_ENV).is_some(),
+            false => self.envs.contains_key(COMPLETE_ENV),
+        }
+    }
+
+    /// Prints the candidates of the value being completed, one per line,
+    /// with the print function set with [`Compose::help`] or to stdout.
+    /// The completion scripts pass the words up to the cursor after a `--`,
+    /// e.g. `demo -- db --url pos` or `demo -- db --url=pos`.
+    fn complete(mut self) -> Result<Matches, Error> {
+        if self.args.first().is_some_and(|arg| arg == "--") {
+            self.args.remove(0);
+        }
+        let partial = self.args.pop().unwrap_or_default();
+        while let Some((index, position)) = self.find_subcommand() {
+            self = self.into_subcommand(index, position);
+        }
+
+        let (flag, mut prefix, partial) = match partial.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (
+                Some(flag.to_string()),
+                format!("{}=", flag),
+                value.to_string(),
+            ),
+            _ => (self.args.last().cloned(), String::new(), partial),
+        };
+        let flags = Flags::new(&self.options);
+        let index = flag.and_then(|flag| match flag.strip_prefix("--") {
+            Some(long) => flags.find(Flag::Long(long)),
+            None => match flag.strip_prefix('-')?.chars().collect::<Vec<_>>()[..] {
+                [short] => flags.find(Flag::Short(short)),
+                _ => None,
+            },
+        });
+
+        let mut candidates = Vec::new();
+        if let Some(option) = index.map(|index| &self.options[index]) {
+            // Only the last of comma separated values is completed.
+            let partial = match (option.info().multiple, partial.rsplit_once(',')) {
+                (true, Some((values, partial))) => {
+                    prefix.push_str(&format!("{},", values));
+                    partial
+                }
+                _ => partial.as_str(),
+            };
+            candidates = option
+                .complete(partial)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|candidate| format!("{}{}", prefix, candidate))
+                .collect();
+        }
+
+        self.print_candidates(&candidates, &mut std::io::stdout());
+        Err(Error::CompletionRequested)
+    }
+
+    /// Prints the candidates with the print function set with [`Compose::help`],
+    /// or writes them to `stdout` for the completion script if none is set.
+    fn print_candidates(&self, candidates: &[String], stdout: &mut dyn std::io::Write) {
+        match &self.print_fn {
+            Some(print_fn) => print_fn(candidates.join("\n")),
+            None => candidates
+                .iter()
+                .for_each(|candidate| _ = writeln!(stdout, "{}", candidate)),
+        }
+    }
 
-        Ok(())
+    fn help_requested(&self) -> bool {
+        let (long, short) = self.help_flags();
+        self.flag_given(long, short)
+    }
+
+    fn version_requested(&self) -> bool {
+        let (long, short) = self.version_flags();
+        self.flag_given(long, short)
+    }
+
+    /// Returns the help flags that are not taken by an option.
+    fn help_flags(&self) -> (Option<&str>, Option<char>) {
+        self.unclaimed_flags(Some(&self.help_long), self.help_short)
+    }
+
+    /// Returns the `--version` and `-V` flags that are not taken by an option.
+    fn version_flags(&self) -> (Option<&str>, Option<char>) {
+        self.unclaimed_flags(Some("version"), Some('V'))
+    }
+
+    fn unclaimed_flags<'f>(
+        &self,
+        long: Option<&'f str>,
+        short: Option<char>,
+    ) -> (Option<&'f str>, Option<char>) {
+        let flags = Flags::new(&self.options);
+        let long = long.filter(|long| flags.find(Flag::Long(long)).is_none());
+        let short = short.filter(|short| flags.find(Flag::Short(*short)).is_none());
+        (long, short)
+    }
+
+    /// Returns `true` if the flag is given before the `--` terminator.
+    fn flag_given(&self, long: Option<&str>, short: Option<char>) -> bool {
+        self.tokenize(&Flags::new(&self.options))
+            .iter()
+            .take_while(|token| token.kind != TokenKind::Terminator)
+            .any(|token| match &token.kind {
+                TokenKind::Long(given) => Some(given.as_str()) == long,
+                TokenKind::Short(given) => Some(*given) == short,
+                _ => false,
+            })
+    }
+
+    /// Returns the name set with [`Compose::name`] or
+    /// the file stem of the executable, e.g. `demo` for `/usr/bin/demo`.
+    fn program_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        std::env::args_os()
+            .next()
+            .and_then(|arg| {
+                Path::new(&arg)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "<program>".into())
+    }
+
+    /// Sets the program name shown in the help and version output.
+    /// Defaults to the file stem of the executable.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the description shown at the top of the help.
+    pub fn about(mut self, about: &str) -> Self {
+        self.about = Some(about.into());
+        self
+    }
+
+    /// Sets the text shown at the end of the help, e.g. examples.
+    pub fn after_help(mut self, after_help: &str) -> Self {
+        self.after_help = Some(after_help.into());
+        self
+    }
+
+    /// Sets the width the help is wrapped to.
+    /// Defaults to the `COLUMNS` environment variable or 80 characters.
+    pub fn help_width(mut self, width: usize) -> Self {
+        self.help_width = Some(width);
+        self
+    }
+
+    /// Sets the version printed by `--version`/`-V` as `<name> <version>`.
+    /// Use [`crate_version!`](crate::crate_version) for the version of the calling crate.
+    /// A flag that is already used by an option is left to the option.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the flags that print the help, `--help` and `-h` by default.
+    /// Also applies to subcommands. A flag that is already used by an option is left to the option.
+    pub fn help_flag(mut self, long: &str, short: Option<char>) -> Self {
+        self.help_long = long.into();
+        self.help_short = short;
+        self
     }
 
     pub fn help(mut self, print_fn: impl Fn(String) + 'a) -> Self {
         self.print_fn = Some(Box::new(print_fn));
         self
     }
+
+    /// Generates a completion script for `shell` that completes the options
+    /// and subcommands of the program `bin_name`.
+    pub fn generate_completions(&self, shell: Shell, bin_name: &str) -> String {
+        completions::generate(shell, &self.program_info(bin_name), bin_name)
+    }
+
+    /// Renders a man page in the `man(7)` format with the sections NAME, SYNOPSIS,
+    /// DESCRIPTION, OPTIONS, COMMANDS and ENVIRONMENT.
+    pub fn render_man(&self) -> String {
+        let command = self.program_info(&self.program_name());
+        let name = escape(&command.name);
+        let mut page = format!(".TH {} 1", escape(&command.name.to_uppercase()));
+        if let Some(version) = &self.version {
+            page.push_str(&format!(" \"\" \"{} {}\"", name, escape(version)));
+        }
+
+        page.push_str("\n.SH NAME\n");
+        match &command.about {
+            Some(about) => page.push_str(&format!("{} \\- {}\n", name, escape(about))),
+            None => page.push_str(&format!("{}\n", name)),
+        }
+        let usage = self.render_usage();
+        page.push_str(&format!(
+            ".SH SYNOPSIS\n\\fB{}\\fR{}\n",
+            name,
+            escape(usage.strip_prefix(command.name.as_str()).unwrap_or(&usage))
+        ));
+        let description: Vec<String> = [&command.about, &self.after_help]
+            .into_iter()
+            .flatten()
+            .map(|text| escape(text))
+            .collect();
+        if !description.is_empty() {
+            page.push_str(&format!(
+                ".SH DESCRIPTION\n{}\n",
+                description.join("\n.PP\n")
+            ));
+        }
+
+        // Positional arguments first, then the options of the default section.
+        let (mut options, grouped): (Vec<_>, Vec<_>) = command
+            .options
+            .iter()
+            .partition(|info| info.group.is_none());
+        options.sort_by_key(|info| info.positional.is_none());
+        page.push_str(".SH OPTIONS\n");
+        page.extend(options.into_iter().filter_map(option_item));
+        let mut groups: Vec<&str> = Vec::new();
+        for group in grouped.iter().filter_map(|info| info.group.as_deref()) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        for group in groups {
+            page.push_str(&format!(".SS {}\n", escape(group)));
+            page.extend(
+                grouped
+                    .iter()
+                    .filter(|info| info.group.as_deref() == Some(group))
+                    .filter_map(|info| option_item(info)),
+            );
+        }
+
+        if !command.subcommands.is_empty() {
+            page.push_str(".SH COMMANDS\n");
+            for subcommand in &command.subcommands {
+                page.push_str(&paragraph(
+                    &format!("\\fB{}\\fR", escape(&subcommand.name)),
+                    subcommand.about.as_deref().unwrap_or_default(),
+                ));
+            }
+        }
+
+        let environment: String = command
+            .options
+            .iter()
+            .filter_map(environment_item)
+            .collect();
+        if !environment.is_empty() {
+            page.push_str(&format!(".SH ENVIRONMENT\n{}", environment));
+        }
+
+        page
+    }
+
+    /// Renders a Markdown reference with the usage, a table of the options
+    /// and a section per subcommand.
+    pub fn render_markdown(&self) -> String {
+        let command = self.program_info(&self.program_name());
+        let mut document = format!("# {}\n", command.name);
+        if let Some(about) = &command.about {
+            document.push_str(&format!("\n{}\n", about));
+        }
+        document.push_str(&format!(
+            "\n## Usage\n\n```text\n{}\n```\n",
+            self.render_usage()
+        ));
+        document.push_str(&format!(
+            "\n## Options\n\n{}",
+            options_table(&command.options)
+        ));
+        if let Some(after_help) = &self.after_help {
+            document.push_str(&format!("\n{}\n", after_help));
+        }
+        document.push_str(&subcommand_sections(&command, &command.name));
+        document
+    }
+
+    /// Returns the command info of the program including the help and version flags.
+    fn program_info(&self, name: &str) -> CommandInfo {
+        let mut command = self.command_info(name);
+        let (long, short) = self.help_flags();
+        if long.is_some() || short.is_some() {
+            command.options.push(OptionInfo {
+                description: "Print help".into(),
+                short_arg: short,
+                long_arg: long.map(String::from),
+                is_switch: true,
+                value_type: "bool".into(),
+                ..Default::default()
+            });
+        }
+        let (long, short) = self.version_flags();
+        if self.version.is_some() && (long.is_some() || short.is_some()) {
+            command.options.push(OptionInfo {
+                description: "Print version".into(),
+                short_arg: short,
+                long_arg: long.map(String::from),
+                is_switch: true,
+                value_type: "bool".into(),
+                ..Default::default()
+            });
+        }
+        command
+    }
+
+    fn command_info(&self, name: &str) -> CommandInfo {
+        CommandInfo {
+            name: name.into(),
+            about: self.about.clone(),
+            options: self.options.iter().map(|option| option.info()).collect(),
+            subcommands: self
+                .subcommands
+                .iter()
+                .map(|(name, subcommand)| subcommand.command_info(name))
+                .collect(),
+        }
+    }
+
+    fn render_help(&self) -> String {
+        let mut help = String::new();
+        if let Some(about) = &self.about {
+            help.push_str(&format!("{}\n\n", about));
+        }
+        help.push_str(&format!("Usage: {}\n\n", self.render_usage()));
+
+        let (arguments, options): (Vec<_>, Vec<_>) = self
+            .options
+            .iter()
+            .map(|option| option.info())
+            .partition(|info| info.positional.is_some());
+        let mut groups: Vec<Section> = Vec::new();
+        let mut ungrouped = Vec::new();
+        for info in &options {
+            let Some(group) = &info.group else {
+                ungrouped.push(option_entry(info));
+                continue;
+            };
+            match groups.iter_mut().find(|section| section.heading == *group) {
+                Some(section) => section.entries.push(option_entry(info)),
+                None => groups.push(Section {
+                    heading: group.clone(),
+                    entries: vec![option_entry(info)],
+                }),
+            }
+        }
+        let mut options = ungrouped;
+        let (long, short) = self.help_flags();
+        if let Some(flags) = flags_column(long, short) {
+            options.push((flags, "Print help".into()));
+        }
+        if self.version.is_some() {
+            let (long, short) = self.version_flags();
+            if let Some(flags) = flags_column(long, short) {
+                options.push((flags, "Print version".into()));
+            }
+        }
+        let mut sections = vec![
+            Section {
+                heading: "Arguments".into(),
+                entries: arguments.iter().map(option_entry).collect(),
+            },
+            Section {
+                heading: "Options".into(),
+                entries: options,
+            },
+        ];
+        sections.append(&mut groups);
+        sections.push(Section {
+            heading: "Commands".into(),
+            entries: self
+                .subcommands
+                .iter()
+                .map(|(name, subcommand)| {
+                    (name.clone(), subcommand.about.clone().unwrap_or_default())
+                })
+                .collect(),
+        });
+        help.push_str(&render_sections(&sections, self.wrap_width()));
+
+        if let Some(after_help) = &self.after_help {
+            help.push_str(&format!("\n{}\n", after_help));
+        }
+
+        help
+    }
+
+    /// Returns the width the help is wrapped to, taken from [`Compose::help_width`],
+    /// the `COLUMNS` environment variable or [`help::DEFAULT_WIDTH`].
+    fn wrap_width(&self) -> usize {
+        self.help_width
+            .or_else(|| {
+                self.envs
+                    .get("COLUMNS")
+                    .cloned()
+                    .or_else(|| std::env::var("COLUMNS").ok())
+                    .and_then(|columns| columns.trim().parse().ok())
+            })
+            .unwrap_or(help::DEFAULT_WIDTH)
+    }
+
+    /// Renders the usage line, listing the required flags before the positionals,
+    /// e.g. `demo db -n <NAME> [OPTIONS] <file> <COMMAND>`.
+    fn render_usage(&self) -> String {
+        let mut usage = self.program_name();
+        for (_, name) in &self.path {
+            usage.push_str(&format!(" {}", name));
+        }
+        let (positionals, flags): (Vec<_>, Vec<_>) = self
+            .options
+            .iter()
+            .partition(|option| option.positional().is_some());
+        for flag in flags.iter().filter_map(|option| option.usage()) {
+            usage.push_str(&format!(" {}", flag));
+        }
+        usage.push_str(" [OPTIONS]");
+        for positional in positionals.iter().filter_map(|option| option.usage()) {
+            usage.push_str(&format!(" {}", positional));
+        }
+        if !self.subcommands.is_empty() {
+            usage.push_str(" <COMMAND>");
+        }
+        usage
+    }
+
+    fn unknown_argument(&self, token: &Token) -> Error {
+        let argument = match &token.kind {
+            // A long flag without a name, e.g. `--=x`, is reported as given.
+            TokenKind::Long(long) if long.is_empty() => self.args[token.position].clone(),
+            TokenKind::Long(long) => format!("--{}", long),
+            TokenKind::Short(short) => format!("-{}", short),
+            TokenKind::Value(value) | TokenKind::Positional(value) => value.clone(),
+            TokenKind::Terminator => "--".to_string(),
+        };
+        let error = Error::new(ErrorKind::UnknownArgument)
+            .with_input(argument.as_str())
+            .with_source(Source::Arg(token.position + 1));
+        let named_flag = match &token.kind {
+            TokenKind::Long(long) => !long.is_empty(),
+            TokenKind::Short(_) => true,
+            _ => false,
+        };
+        if !named_flag {
+            return error;
+        }
+
+        let mut flags = Vec::new();
+        let (long, short) = self.help_flags();
+        flags.extend(long.map(|long| format!("--{}", long)));
+        flags.extend(short.map(|short| format!("-{}", short)));
+        if self.version.is_some() {
+            let (long, short) = self.version_flags();
+            flags.extend(long.map(|long| format!("--{}", long)));
+            flags.extend(short.map(|short| format!("-{}", short)));
+        }
+        for option in &self.options {
+            if let Some(long) = option.long_arg() {
+                flags.push(format!("--{}", long));
+            }
+            if let Some(short) = option.short_arg() {
+                flags.push(format!("-{}", short));
+            }
+        }
+
+        match did_you_mean(&argument, flags.iter().map(String::as_str)) {
+            Some(suggestion) => error.with_suggestion(suggestion),
+            None => error,
+        }
+    }
+}
+
+/// Returns the left help column of a built-in flag, `None` if both flags are taken.
+fn flags_column(long: Option<&str>, short: Option<char>) -> Option<String> {
+    match (long, short) {
+        (Some(long), Some(short)) => Some(format!("-{}, --{}", short, long)),
+        (Some(long), None) => Some(format!("    --{}", long)),
+        (None, Some(short)) => Some(format!("-{}", short)),
+        (None, None) => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::error::Error;
-    use crate::options::OptionBase;
+    use crate::error::{Error, ErrorKind, Source};
+    use crate::options::{
+        OptionBase, OptionInfo, PositionalArity, PositionalOption, PositionalValues, ValueOption,
+        ValuesOption,
+    };
     use std::collections::HashMap;
 
     pub struct MockOption {
         expected_env: HashMap<String, String>,
-        expected_args: Vec<String>,
+        expected_arg: (Option<String>, usize),
         eval_result: Result<(), Error>,
     }
     impl OptionBase for MockOption {
-        fn parse_env(&mut self, envs: &std::collections::HashMap<String, String>) {
+        fn parse_env(
+            &mut self,
+            envs: &std::collections::HashMap<String, String>,
+        ) -> Result<(), Error> {
             assert_eq!(self.expected_env, *envs);
+            Ok(())
         }
-        fn parse_args(&mut self, _args: &[String]) {
-            assert_eq!(self.expected_args, _args);
+        fn parse_arg(&mut self, value: Option<&str>, position: usize) -> Result<(), Error> {
+            assert_eq!(self.expected_arg, (value.map(String::from), position));
+            Ok(())
         }
         fn eval(&mut self) -> Result<(), Error> {
             self.eval_result.clone()
         }
-        fn help(&self) -> String {
-            "MockOption".to_string()
+        fn info(&self) -> OptionInfo {
+            OptionInfo {
+                description: "MockOption".to_string(),
+                long_arg: Some("test".to_string()),
+                ..Default::default()
+            }
+        }
+        fn long_arg(&self) -> Option<&str> {
+            Some("test")
+        }
+        fn short_arg(&self) -> Option<char> {
+            None
+        }
+        fn is_switch(&self) -> bool {
+            false
+        }
+        fn positional(&self) -> Option<PositionalArity> {
+            None
+        }
+        fn usage(&self) -> Option<String> {
+            None
+        }
+        fn take_match(&mut self) -> Option<(String, Box<dyn std::any::Any>)> {
+            None
+        }
+        fn complete(&self, _: &str) -> Option<Vec<String>> {
+            None
         }
     }
 
@@ -127,7 +991,7 @@ mod tests {
 
         compose = compose.add(MockOption {
             expected_env: env.clone(),
-            expected_args: args.clone(),
+            expected_arg: (Some("test_value".to_string()), 1),
             eval_result: Ok(()),
         });
         assert_eq!(compose.options.len(), 1);
@@ -136,6 +1000,1117 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn parse_propagate_conversion_error() {
+        let mut age = None::<u8>;
+        let result = super::Compose::new()
+            .args(vec!["--age".to_string(), "abc".to_string()].into_iter())
+            .add(ValueOption::new(&mut age, "Your age").long_arg("age"))
+            .parse();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("Parsing error: {--age} failed to parse argument at position 2"));
+    }
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn assert_unknown_argument(result: Result<(), Error>, input: &str) {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(error.details().unwrap().input.as_deref(), Some(input));
+    }
+
+    #[test]
+    fn parse_long_and_short() {
+        let (mut name, mut age) = (None::<String>, None::<u8>);
+        let result = super::Compose::new()
+            .args(args(&["--name", "alice", "-a", "42"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .add(ValueOption::new(&mut age, "age").short_arg('a'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, Some("alice".to_string()));
+        assert_eq!(age, Some(42));
+    }
+
+    #[test]
+    fn parse_attached_values() {
+        let (mut name, mut age, mut skills) = (None::<String>, None::<u8>, None::<Vec<String>>);
+        let result = super::Compose::new()
+            .args(args(&["--name=alice", "-a42", "-s=a,b", "--skills=c"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .add(ValueOption::new(&mut age, "age").short_arg('a'))
+            .add(
+                ValuesOption::new(&mut skills, "skills")
+                    .short_arg('s')
+                    .long_arg("skills"),
+            )
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, Some("alice".to_string()));
+        assert_eq!(age, Some(42));
+        assert_eq!(
+            skills,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_long_prefix_not_matched() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--names", "alice"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .allow_unknown()
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, None);
+    }
+
+    #[test]
+    fn parse_unknown_flag_with_suggestion() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--nmae", "alice"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .parse();
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        let details = error.details().unwrap();
+        assert_eq!(details.input.as_deref(), Some("--nmae"));
+        assert_eq!(details.suggestion.as_deref(), Some("--name"));
+    }
+
+    #[test]
+    fn parse_unknown_short_flag() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["-x"]))
+            .add(ValueOption::new(&mut name, "name").short_arg('n'))
+            .parse();
+        assert_unknown_argument(result, "-x");
+    }
+
+    #[test]
+    fn parse_unknown_nameless_long_flag() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--=x"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .parse();
+        let error = result.unwrap_err();
+        assert_eq!(error.to_string(), "Unknown argument: --=x");
+        assert_eq!(error.details().unwrap().suggestion, None);
+    }
+
+    #[test]
+    fn parse_stray_argument() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--name", "alice", "bob"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .parse();
+        assert_unknown_argument(result, "bob");
+    }
+
+    #[test]
+    fn parse_allow_unknown() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--verbose", "--name", "alice", "bob"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .allow_unknown()
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, Some("alice".to_string()));
+    }
+
+    #[test]
+    fn parse_value_taken_verbatim() {
+        let (mut name, mut verbose) = (None::<String>, None::<bool>);
+        let result = super::Compose::new()
+            .args(args(&["--name", "-v"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, Some("-v".to_string()));
+        assert_eq!(verbose, None);
+    }
+
+    #[test]
+    fn parse_switch_does_not_take_next() {
+        let (mut employed, mut name) = (None::<bool>, None::<String>);
+        let result = super::Compose::new()
+            .args(args(&["--employed", "--name", "bob"]))
+            .add(ValueOption::new(&mut employed, "employed").long_arg("employed"))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(employed, Some(true));
+        assert_eq!(name, Some("bob".to_string()));
+    }
+
+    #[test]
+    fn parse_switch_followed_by_positional() {
+        let mut verbose = None::<bool>;
+        let result = super::Compose::new()
+            .args(args(&["--verbose", "file.txt"]))
+            .add(ValueOption::new(&mut verbose, "verbose").long_arg("verbose"))
+            .parse();
+        assert_unknown_argument(result, "file.txt");
+    }
+
+    #[test]
+    fn parse_switch_attached_value() {
+        let mut employed = None::<bool>;
+        let result = super::Compose::new()
+            .args(args(&["--employed=false"]))
+            .add(
+                ValueOption::new(&mut employed, "employed")
+                    .long_arg("employed")
+                    .default(true),
+            )
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(employed, Some(false));
+    }
+
+    #[test]
+    fn parse_positionals() {
+        let (mut verbose, mut source, mut target) = (None::<bool>, None::<String>, None::<String>);
+        let result = super::Compose::new()
+            .args(args(&["a.txt", "-v", "b.txt"]))
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .add(PositionalOption::new(&mut source, "source", "source"))
+            .add(PositionalOption::new(&mut target, "target", "target"))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(verbose, Some(true));
+        assert_eq!(source, Some("a.txt".to_string()));
+        assert_eq!(target, Some("b.txt".to_string()));
+    }
+
+    #[test]
+    fn parse_variadic_positional() {
+        let (mut command, mut files, mut name) = (None::<String>, None::<Vec<String>>, None);
+        let result = super::Compose::new()
+            .args(args(&["copy", "a.txt", "--name", "alice", "b.txt"]))
+            .add(PositionalOption::new(&mut command, "command", "command"))
+            .add(PositionalValues::new(&mut files, "files", "files"))
+            .add(ValueOption::<String>::new(&mut name, "name").long_arg("name"))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(command, Some("copy".to_string()));
+        assert_eq!(files, Some(vec!["a.txt".to_string(), "b.txt".to_string()]));
+        assert_eq!(name, Some("alice".to_string()));
+    }
+
+    #[test]
+    fn parse_too_many_positionals() {
+        let mut file = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["a.txt", "b.txt"]))
+            .add(PositionalOption::new(&mut file, "file", "file"))
+            .parse();
+        assert_unknown_argument(result, "b.txt");
+    }
+
+    #[test]
+    fn parse_required_positional_missing() {
+        let mut file = None::<String>;
+        let result = super::Compose::new()
+            .add(PositionalOption::new(&mut file, "file", "file").required())
+            .parse();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            Error::Validation("{<file>} is required".into()).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_collects_all_errors() {
+        let (mut name, mut age, mut score) = (None::<String>, None::<u8>, None::<u8>);
+        let result = super::Compose::new()
+            .args(args(&["--age", "abc", "--score", "5", "--nmae"]))
+            .add(
+                ValueOption::new(&mut name, "name")
+                    .long_arg("name")
+                    .required(),
+            )
+            .add(ValueOption::new(&mut age, "age").long_arg("age").required())
+            .add(
+                ValueOption::new(&mut score, "score")
+                    .long_arg("score")
+                    .additional_eval(|score| match *score > 10 {
+                        true => Ok(()),
+                        false => Err(Error::Validation("too low".into())),
+                    }),
+            )
+            .parse();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            Error::Multiple(vec![
+                Error::Parsing(
+                    "{--age} failed to parse argument at position 2: \
+                     Error converting from \"abc\" to u8"
+                        .into()
+                ),
+                Error::new(ErrorKind::UnknownArgument)
+                    .with_input("--nmae")
+                    .with_suggestion("--name"),
+                Error::Validation("{--name} is required".into()),
+                Error::Validation("{--score} failed validation: too low".into()),
+            ])
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn parse_error_details() {
+        let (mut name, mut age) = (None::<String>, None::<u8>);
+        let result = super::Compose::new()
+            .args(args(&["--age", "abc", "--nmae"]))
+            .add(
+                ValueOption::new(&mut name, "name")
+                    .long_arg("name")
+                    .required(),
+            )
+            .add(ValueOption::new(&mut age, "age").env("AGE").long_arg("age"))
+            .parse();
+        let Err(Error::Multiple(errors)) = result else {
+            panic!("expected multiple errors");
+        };
+        let kinds: Vec<ErrorKind> = errors.iter().map(Error::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ErrorKind::InvalidValue,
+                ErrorKind::UnknownArgument,
+                ErrorKind::MissingRequired
+            ]
+        );
+
+        let details = errors[0].details().unwrap();
+        let option = details.option.as_ref().unwrap();
+        assert_eq!(option.env_key.as_deref(), Some("AGE"));
+        assert_eq!(option.long_arg.as_deref(), Some("age"));
+        assert_eq!(details.input.as_deref(), Some("abc"));
+        assert_eq!(details.source, Some(Source::Arg(2)));
+
+        let details = errors[1].details().unwrap();
+        assert_eq!(details.input.as_deref(), Some("--nmae"));
+        assert_eq!(details.suggestion.as_deref(), Some("--name"));
+        assert_eq!(details.source, Some(Source::Arg(3)));
+    }
+
+    #[test]
+    fn parse_env_error_overridden_by_arg() {
+        let mut age = None::<u8>;
+        let result = super::Compose::new()
+            .envs([("AGE".to_string(), "abc".to_string())].into_iter())
+            .args(args(&["--age", "5"]))
+            .add(ValueOption::new(&mut age, "age").env("AGE").long_arg("age"))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(age, Some(5));
+
+        let result = super::Compose::new()
+            .envs([("AGE".to_string(), "abc".to_string())].into_iter())
+            .add(ValueOption::new(&mut age, "age").env("AGE").long_arg("age"))
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn parse_validation_error_source() {
+        let mut age = None::<u8>;
+        let result = super::Compose::new()
+            .envs([("AGE".to_string(), "12".to_string())].into_iter())
+            .add(
+                ValueOption::new(&mut age, "age")
+                    .env("AGE")
+                    .additional_eval(|_| Err(Error::Validation("too young".into()))),
+            )
+            .parse();
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValidationFailed);
+        let details = error.details().unwrap();
+        assert_eq!(details.source, Some(Source::Env("AGE".into())));
+        assert_eq!(details.input.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn help_requested() {
+        let compose = super::Compose::new().args(args(&["-h"]));
+        assert!(compose.help_requested());
+        let compose = super::Compose::new().args(args(&["--", "--help"]));
+        assert!(!compose.help_requested());
+    }
+
+    #[test]
+    fn help_skips_validation() {
+        let output = std::cell::RefCell::new(String::new());
+        let (mut name, mut age) = (None::<String>, None::<u8>);
+        let result = super::Compose::new()
+            .args(args(&["--age", "abc", "--help"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .add(
+                ValueOption::new(&mut name, "name")
+                    .long_arg("name")
+                    .required(),
+            )
+            .add(ValueOption::new(&mut age, "age").long_arg("age"))
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::HelpRequested);
+        assert!(output.borrow().starts_with("Usage: "));
+        assert_eq!(age, None);
+    }
+
+    #[test]
+    fn help_name_about_and_usage() {
+        let output = std::cell::RefCell::new(String::new());
+        let (mut name, mut age, mut file) = (None::<String>, None::<u8>, None::<String>);
+        let _ = super::Compose::new()
+            .args(args(&["-h"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .name("demo")
+            .about("Greets people.")
+            .after_help("Example: demo -n alice")
+            .add(
+                ValueOption::new(&mut name, "name")
+                    .short_arg('n')
+                    .long_arg("name")
+                    .value_name("NAME")
+                    .required(),
+            )
+            .add(ValueOption::new(&mut age, "age").short_arg('a'))
+            .add(PositionalOption::new(&mut file, "file", "file"))
+            .parse();
+        let output = output.borrow();
+        assert!(output.starts_with("Greets people.\n\nUsage: demo -n <NAME> [OPTIONS] [file]\n\n"));
+        assert!(output.ends_with("\nExample: demo -n alice\n"));
+    }
+
+    #[test]
+    fn help_groups() {
+        let output = std::cell::RefCell::new(String::new());
+        let (mut host, mut port, mut verbose, mut tags) = (None::<String>, None::<u16>, None, None);
+        let _ = super::Compose::new()
+            .args(args(&["--help"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .name("demo")
+            .add(
+                ValueOption::new(&mut host, "host")
+                    .long_arg("host")
+                    .value_name("HOST")
+                    .group("Database"),
+            )
+            .add(ValueOption::<bool>::new(&mut verbose, "verbose").short_arg('v'))
+            .add(
+                ValuesOption::<String>::new(&mut tags, "tags")
+                    .long_arg("tags")
+                    .value_name("TAG")
+                    .group("Output"),
+            )
+            .add(
+                ValueOption::new(&mut port, "port")
+                    .long_arg("port")
+                    .group("Database"),
+            )
+            .parse();
+        assert_eq!(
+            *output.borrow(),
+            "Usage: demo [OPTIONS]\n\n\
+             Options:\n  \
+             -v                    verbose\n  \
+             -h, --help            Print help\n\
+             \n\
+             Database:\n      \
+             --host <HOST>     host\n      \
+             --port <INT>      port\n\
+             \n\
+             Output:\n      \
+             --tags <TAG>,...  tags\n"
+        );
+    }
+
+    #[test]
+    fn help_wrapped_to_columns() {
+        let output = std::cell::RefCell::new(String::new());
+        let mut name = None::<String>;
+        let _ = super::Compose::new()
+            .args(args(&["--help"]))
+            .envs([("COLUMNS".to_string(), "50".to_string())].into_iter())
+            .help(|help| output.borrow_mut().push_str(&help))
+            .name("demo")
+            .add(
+                ValueOption::new(&mut name, "The name used to greet you")
+                    .env("NAME")
+                    .short_arg('n')
+                    .long_arg("name")
+                    .default("world".to_string()),
+            )
+            .parse();
+        assert_eq!(
+            *output.borrow(),
+            "Usage: demo [OPTIONS]\n\n\
+             Options:\n  \
+             -n, --name <STRING>  The name used to greet you\n                       \
+             [env: NAME]\n                       \
+             [default: \"world\"]\n  \
+             -h, --help           Print help\n"
+        );
+
+        output.borrow_mut().clear();
+        let _ = super::Compose::new()
+            .args(args(&["--help"]))
+            .envs([("COLUMNS".to_string(), "40".to_string())].into_iter())
+            .help(|help| output.borrow_mut().push_str(&help))
+            .help_width(100)
+            .name("demo")
+            .add(ValueOption::new(&mut name, "The name used to greet you").long_arg("name"))
+            .parse();
+        assert!(output
+            .borrow()
+            .contains("--name <STRING>  The name used to greet you\n"));
+    }
+
+    #[test]
+    fn version_uses_name() {
+        let output = std::cell::RefCell::new(String::new());
+        let _ = super::Compose::new()
+            .args(args(&["--version"]))
+            .help(|text| output.borrow_mut().push_str(&text))
+            .name("demo")
+            .version("1.2.3")
+            .parse();
+        assert_eq!(*output.borrow(), "demo 1.2.3");
+    }
+
+    #[test]
+    fn version() {
+        let output = std::cell::RefCell::new(String::new());
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["db", "-V"]))
+            .help(|text| output.borrow_mut().push_str(&text))
+            .version("1.2.3")
+            .add(
+                ValueOption::new(&mut name, "name")
+                    .long_arg("name")
+                    .required(),
+            )
+            .subcommand("db", super::Compose::new())
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::VersionRequested);
+        assert!(output.borrow().ends_with(" 1.2.3"));
+
+        let result = super::Compose::new().args(args(&["--version"])).parse();
+        assert_unknown_argument(result, "--version");
+    }
+
+    #[test]
+    fn version_short_flag_taken_by_option() {
+        let output = std::cell::RefCell::new(String::new());
+        let mut verbose = None::<bool>;
+        let result = super::Compose::new()
+            .args(args(&["-V"]))
+            .help(|text| output.borrow_mut().push_str(&text))
+            .version("1.2.3")
+            .add(ValueOption::new(&mut verbose, "Verbose output").short_arg('V'))
+            .parse();
+        assert!(result.is_ok());
+        assert!(output.borrow().is_empty());
+        assert_eq!(verbose, Some(true));
+
+        let mut verbose = None::<bool>;
+        let compose = super::Compose::new()
+            .version("1.2.3")
+            .add(ValueOption::new(&mut verbose, "Verbose output").short_arg('V'));
+        assert!(compose
+            .render_help()
+            .contains("    --version  Print version\n"));
+        let version = compose.program_info("demo").options.pop().unwrap();
+        assert_eq!(version.short_arg, None);
+        assert_eq!(version.long_arg.as_deref(), Some("version"));
+    }
+
+    #[test]
+    fn generate_completions() {
+        let mut name = None::<String>;
+        let compose = super::Compose::new()
+            .version("1.2.3")
+            .help_flag("usage", None)
+            .add(ValueOption::new(&mut name, "Your name").short_arg('n'))
+            .subcommand("db", super::Compose::new().about("Database"));
+        assert_eq!(
+            compose.generate_completions(super::Shell::Fish, "demo"),
+            "complete -c demo -s n -d 'Your name' -r\n\
+             complete -c demo -l usage -d 'Print help'\n\
+             complete -c demo -s V -l version -d 'Print version'\n\
+             complete -c demo -n '__fish_use_subcommand' -f -a 'db' -d 'Database'\n"
+        );
+    }
+
+    #[test]
+    fn complete_values() {
+        let complete = |words: &[&str]| {
+            let output = std::cell::RefCell::new(None);
+            let mut profile = None::<String>;
+            let mut tables = None::<Vec<String>>;
+            let result = super::Compose::new()
+                .args(args(words))
+                .envs([("ROPTS_COMPLETE".to_string(), "bash".to_string())].into_iter())
+                .help(|text| *output.borrow_mut() = Some(text))
+                .add(
+                    ValueOption::new(&mut profile, "Profile")
+                        .short_arg('p')
+                        .long_arg("profile")
+                        .required()
+                        .complete_with(|partial| {
+                            ["dev", "prod"]
+                                .iter()
+                                .filter(|profile| profile.starts_with(partial))
+                                .map(|profile| profile.to_string())
+                                .collect()
+                        }),
+                )
+                .subcommand(
+                    "db",
+                    super::Compose::new().add(
+                        ValuesOption::new(&mut tables, "Tables")
+                            .long_arg("tables")
+                            .complete_with(|_| vec!["users".into(), "posts".into()]),
+                    ),
+                )
+                .parse();
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::CompletionRequested);
+            output.into_inner().unwrap()
+        };
+
+        assert_eq!(complete(&["--", "-p", ""]), "dev\nprod");
+        assert_eq!(complete(&["--", "--profile", "d"]), "dev");
+        assert_eq!(complete(&["--", "--profile=p"]), "--profile=prod");
+        assert_eq!(
+            complete(&["--", "db", "--tables", "users,p"]),
+            "users,users\nusers,posts"
+        );
+        assert_eq!(complete(&["--", "db", "-p", ""]), "dev\nprod");
+        assert_eq!(complete(&["--", "db", ""]), "");
+
+        let compose =
+            super::Compose::new().envs([("HOME".to_string(), "/root".to_string())].into_iter());
+        assert!(!compose.completion_requested());
+    }
+
+    #[test]
+    fn complete_without_print_fn() {
+        let candidates = vec!["dev".to_string(), "prod".to_string()];
+        let mut stdout = Vec::new();
+        super::Compose::new().print_candidates(&candidates, &mut stdout);
+        assert_eq!(String::from_utf8(stdout).unwrap(), "dev\nprod\n");
+
+        let result = super::Compose::new()
+            .args(args(&["--", "--profile", ""]))
+            .envs([("ROPTS_COMPLETE".to_string(), "bash".to_string())].into_iter())
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::CompletionRequested);
+    }
+
+    #[test]
+    fn render_man() {
+        let (mut name, mut host, mut token, mut file) = (
+            None::<String>,
+            None::<String>,
+            None::<String>,
+            None::<String>,
+        );
+        let page = super::Compose::new()
+            .name("demo")
+            .version("1.2.3")
+            .about("Greets people.")
+            .after_help("Example: demo -n alice")
+            .add(
+                ValueOption::new(&mut name, "Your name")
+                    .env("DEMO_NAME")
+                    .short_arg('n')
+                    .long_arg("name")
+                    .value_name("NAME")
+                    .required(),
+            )
+            .add(
+                ValueOption::new(&mut host, "Database host")
+                    .long_arg("host")
+                    .value_name("HOST")
+                    .group("Database"),
+            )
+            .add(ValueOption::new(&mut token, "Secret token").env("DEMO_TOKEN"))
+            .add(PositionalOption::new(&mut file, "file", "Input file"))
+            .subcommand("db", super::Compose::new().about("Database commands"))
+            .render_man();
+        assert_eq!(
+            page,
+            ".TH DEMO 1 \"\" \"demo 1.2.3\"\n\
+             .SH NAME\n\
+             demo \\- Greets people.\n\
+             .SH SYNOPSIS\n\
+             \\fBdemo\\fR \\-n <NAME> [OPTIONS] [file] <COMMAND>\n\
+             .SH DESCRIPTION\n\
+             Greets people.\n\
+             .PP\n\
+             Example: demo \\-n alice\n\
+             .SH OPTIONS\n\
+             .TP\n\\fI[file]\\fR\nInput file\n\
+             .TP\n\\fB\\-n\\fR, \\fB\\-\\-name\\fR \\fINAME\\fR\nYour name [env: DEMO_NAME] [required]\n\
+             .TP\n\\fB\\-h\\fR, \\fB\\-\\-help\\fR\nPrint help\n\
+             .TP\n\\fB\\-V\\fR, \\fB\\-\\-version\\fR\nPrint version\n\
+             .SS Database\n\
+             .TP\n\\fB\\-\\-host\\fR \\fIHOST\\fR\nDatabase host\n\
+             .SH COMMANDS\n\
+             .TP\n\\fBdb\\fR\nDatabase commands\n\
+             .SH ENVIRONMENT\n\
+             .TP\n\\fBDEMO_NAME\\fR\nYour name\n\
+             .TP\n\\fBDEMO_TOKEN\\fR\nSecret token\n"
+        );
+    }
+
+    #[test]
+    fn render_markdown() {
+        let (mut name, mut skills, mut dry_run) = (None::<String>, None, None::<bool>);
+        let document = super::Compose::new()
+            .name("demo")
+            .about("Greets people.")
+            .help_flag("help", None)
+            .add(
+                ValueOption::new(&mut name, "Your name")
+                    .env("DEMO_NAME")
+                    .short_arg('n')
+                    .long_arg("name")
+                    .value_name("NAME")
+                    .default("bob".into()),
+            )
+            .add(ValuesOption::<String>::new(&mut skills, "Your skills").long_arg("skills"))
+            .subcommand(
+                "db",
+                super::Compose::new().about("Database commands").add(
+                    ValueOption::new(&mut dry_run, "Only print the queries").long_arg("dry-run"),
+                ),
+            )
+            .render_markdown();
+        assert_eq!(
+            document,
+            "# demo\n\
+             \n\
+             Greets people.\n\
+             \n\
+             ## Usage\n\
+             \n\
+             ```text\n\
+             demo [OPTIONS] <COMMAND>\n\
+             ```\n\
+             \n\
+             ## Options\n\
+             \n\
+             | Flags | Env var | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- | --- |\n\
+             | `-n, --name <NAME>` | `DEMO_NAME` | `String` | `\"bob\"` | no | Your name |\n\
+             | `--skills <STRING>,...` |  | `Vec<String>` |  | no | Your skills |\n\
+             | `--help` |  | `bool` |  | no | Print help |\n\
+             \n\
+             ## demo db\n\
+             \n\
+             Database commands\n\
+             \n\
+             | Flags | Env var | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- | --- |\n\
+             | `--dry-run` |  | `bool` |  | no | Only print the queries |\n"
+        );
+    }
+
+    #[test]
+    fn help_custom_flag() {
+        let output = std::cell::RefCell::new(String::new());
+        let mut host = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["-h", "localhost"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .help_flag("usage", None)
+            .add(ValueOption::new(&mut host, "host").short_arg('h'))
+            .parse();
+        assert!(result.is_ok());
+        assert!(output.borrow().is_empty());
+        assert_eq!(host, Some("localhost".to_string()));
+
+        let result = super::Compose::new()
+            .args(args(&["--usage"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .help_flag("usage", None)
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::HelpRequested);
+        assert!(!output.borrow().is_empty());
+
+        let result = super::Compose::new()
+            .args(args(&["--help"]))
+            .help_flag("usage", None)
+            .parse();
+        assert_unknown_argument(result, "--help");
+    }
+
+    #[test]
+    fn help_flags_taken_by_options() {
+        let output = std::cell::RefCell::new(String::new());
+        let mut host = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["-h", "localhost"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .add(ValueOption::new(&mut host, "host").short_arg('h'))
+            .parse();
+        assert!(result.is_ok());
+        assert!(output.borrow().is_empty());
+        assert_eq!(host, Some("localhost".to_string()));
+
+        let mut host = None::<String>;
+        let compose = super::Compose::new().add(ValueOption::new(&mut host, "host").short_arg('h'));
+        let help = compose.render_help();
+        assert_eq!(help.matches("-h ").count(), 1);
+        assert!(!help.contains("-h, --help"));
+        assert!(help
+            .lines()
+            .any(|line| line.trim_start().starts_with("--help ") && line.ends_with("Print help")));
+
+        let mut topic = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--help", "parsing"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .add(ValueOption::new(&mut topic, "topic").long_arg("help"))
+            .parse();
+        assert!(result.is_ok());
+        assert!(output.borrow().is_empty());
+        assert_eq!(topic, Some("parsing".to_string()));
+
+        let mut topic = None::<String>;
+        let compose =
+            super::Compose::new().add(ValueOption::new(&mut topic, "topic").long_arg("help"));
+        assert!(compose
+            .render_help()
+            .lines()
+            .any(|line| line.trim_start().starts_with("-h ") && line.ends_with("Print help")));
+    }
+
+    #[test]
+    fn help_usage_positionals() {
+        let output = std::cell::RefCell::new(String::new());
+        let (mut file, mut files) = (None::<String>, None::<Vec<String>>);
+        let _ = super::Compose::new()
+            .args(args(&["--help"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .name("demo")
+            .add(PositionalOption::new(&mut file, "file", "file").required())
+            .add(PositionalValues::new(&mut files, "files", "files"))
+            .parse();
+        assert!(output
+            .borrow()
+            .starts_with("Usage: demo [OPTIONS] <file> [files...]\n"));
+    }
+
+    #[test]
+    fn parse_terminator_positionals() {
+        let (mut force, mut files) = (None::<bool>, None::<Vec<String>>);
+        let result = super::Compose::new()
+            .args(args(&["-f", "--", "-rf", "--force"]))
+            .add(
+                ValueOption::new(&mut force, "force")
+                    .short_arg('f')
+                    .long_arg("force"),
+            )
+            .add(PositionalValues::new(&mut files, "files", "files"))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(force, Some(true));
+        assert_eq!(files, Some(vec!["-rf".to_string(), "--force".to_string()]));
+    }
+
+    #[test]
+    fn parse_terminator_trailing() {
+        let (mut file, mut trailing) = (None::<String>, None::<Vec<String>>);
+        let result = super::Compose::new()
+            .args(args(&["a.txt", "--", "ls", "-la", "--"]))
+            .add(PositionalOption::new(&mut file, "file", "file"))
+            .trailing(&mut trailing)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(file, Some("a.txt".to_string()));
+        assert_eq!(
+            trailing,
+            Some(vec!["ls".to_string(), "-la".to_string(), "--".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_terminator_without_receiver() {
+        let mut force = None::<bool>;
+        let result = super::Compose::new()
+            .args(args(&["--", "-f"]))
+            .add(ValueOption::new(&mut force, "force").short_arg('f'))
+            .parse();
+        assert_unknown_argument(result, "-f");
+        assert_eq!(force, None);
+    }
+
+    #[test]
+    fn parse_subcommand() {
+        let (mut verbose, mut dry_run, mut path) = (None::<bool>, None::<bool>, None);
+        let result = super::Compose::new()
+            .args(args(&["db", "migrate", "--dry-run", "-v"]))
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .subcommand(
+                "db",
+                super::Compose::new().subcommand(
+                    "migrate",
+                    super::Compose::new()
+                        .add(ValueOption::new(&mut dry_run, "dry run").long_arg("dry-run")),
+                ),
+            )
+            .subcommand_path(&mut path)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(verbose, Some(true));
+        assert_eq!(dry_run, Some(true));
+        assert_eq!(path, Some(vec!["db".to_string(), "migrate".to_string()]));
+    }
+
+    #[test]
+    fn parse_subcommand_global_before_name() {
+        let (mut name, mut force, mut path) = (None::<String>, None::<bool>, None);
+        let result = super::Compose::new()
+            .args(args(&["--name", "db", "db", "--force"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .subcommand(
+                "db",
+                super::Compose::new().add(ValueOption::new(&mut force, "force").long_arg("force")),
+            )
+            .subcommand_path(&mut path)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(name, Some("db".to_string()));
+        assert_eq!(force, Some(true));
+        assert_eq!(path, Some(vec!["db".to_string()]));
+    }
+
+    #[test]
+    fn parse_subcommand_local_flag_shadows_global() {
+        let (mut verbose, mut value) = (None::<bool>, None::<String>);
+        let result = super::Compose::new()
+            .args(args(&["db", "-v", "x"]))
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .subcommand(
+                "db",
+                super::Compose::new().add(ValueOption::new(&mut value, "value").short_arg('v')),
+            )
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(value, Some("x".to_string()));
+        assert_eq!(verbose, None);
+    }
+
+    #[test]
+    fn parse_subcommand_after_positional() {
+        let (mut file, mut target, mut force, mut path) =
+            (None::<String>, None::<String>, None::<bool>, None);
+        let result = super::Compose::new()
+            .args(args(&["a.txt", "db", "--force", "b.txt"]))
+            .add(PositionalOption::new(&mut file, "file", "file"))
+            .subcommand(
+                "db",
+                super::Compose::new()
+                    .add(ValueOption::new(&mut force, "force").long_arg("force"))
+                    .add(PositionalOption::new(&mut target, "target", "target")),
+            )
+            .subcommand_path(&mut path)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(file, Some("a.txt".to_string()));
+        assert_eq!(target, Some("b.txt".to_string()));
+        assert_eq!(force, Some(true));
+        assert_eq!(path, Some(vec!["db".to_string()]));
+
+        let (mut file, mut path) = (None::<String>, None);
+        let result = super::Compose::new()
+            .args(args(&["db"]))
+            .add(PositionalOption::new(&mut file, "file", "file"))
+            .subcommand("db", super::Compose::new())
+            .subcommand_path(&mut path)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(file, Some("db".to_string()));
+        assert_eq!(path, Some(vec![]));
+    }
+
+    #[test]
+    fn parse_subcommand_not_selected() {
+        let (mut force, mut path) = (None::<bool>, None);
+        let result = super::Compose::new()
+            .subcommand(
+                "db",
+                super::Compose::new().add(ValueOption::new(&mut force, "force").long_arg("force")),
+            )
+            .subcommand_path(&mut path)
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(force, None);
+        assert_eq!(path, Some(vec![]));
+    }
+
+    #[test]
+    fn parse_subcommand_options_are_scoped() {
+        let mut force = None::<bool>;
+        let result = super::Compose::new()
+            .args(args(&["cache", "--force"]))
+            .subcommand(
+                "db",
+                super::Compose::new().add(ValueOption::new(&mut force, "force").long_arg("force")),
+            )
+            .subcommand("cache", super::Compose::new())
+            .parse();
+        assert_unknown_argument(result, "--force");
+    }
+
+    #[test]
+    fn help_subcommand() {
+        let output = std::cell::RefCell::new(String::new());
+        let (mut verbose, mut dry_run) = (None::<bool>, None::<bool>);
+        let _ = super::Compose::new()
+            .args(args(&["db", "--help"]))
+            .help(|help| output.borrow_mut().push_str(&help))
+            .name("demo")
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .subcommand(
+                "db",
+                super::Compose::new()
+                    .add(ValueOption::new(&mut dry_run, "dry run").long_arg("dry-run"))
+                    .subcommand(
+                        "migrate",
+                        super::Compose::new().about("Migrates the database"),
+                    ),
+            )
+            .parse();
+        assert_eq!(
+            *output.borrow(),
+            "Usage: demo db [OPTIONS] <COMMAND>\n\n\
+             Options:\n      \
+             --dry-run  dry run\n  \
+             -v             verbose\n  \
+             -h, --help     Print help\n\
+             \n\
+             Commands:\n  \
+             migrate        Migrates the database\n"
+        );
+    }
+
+    #[test]
+    fn parse_matches() {
+        let mut name = None::<String>;
+        let matches = super::Compose::new()
+            .args(args(&[
+                "db", "-a", "42", "--skills", "a,b", "--name", "alice", "x.txt",
+            ]))
+            .add(ValueOption::<u8>::with_id("age", "age").short_arg('a'))
+            .add(ValuesOption::<String>::with_id("skills", "skills").long_arg("skills"))
+            .add(ValueOption::<bool>::with_id("verbose", "verbose").short_arg('v'))
+            .add(
+                ValueOption::<u8>::with_id("level", "level")
+                    .short_arg('l')
+                    .default(3),
+            )
+            .subcommand(
+                "db",
+                super::Compose::new()
+                    .add(ValueOption::new(&mut name, "name").long_arg("name"))
+                    .add(PositionalOption::<String>::with_id("file", "file")),
+            )
+            .parse_matches()
+            .unwrap();
+        assert_eq!(matches.get::<u8>("age"), Some(&42));
+        assert_eq!(matches.get::<u8>("level"), Some(&3));
+        assert_eq!(
+            matches.get_many::<String>("skills"),
+            Some(&["a".to_string(), "b".to_string()][..])
+        );
+        assert_eq!(matches.get::<bool>("verbose"), None);
+        assert_eq!(matches.get::<String>("file"), Some(&"x.txt".to_string()));
+        assert_eq!(matches.subcommand_path(), &["db".to_string()]);
+        assert_eq!(name, Some("alice".to_string()));
+    }
+
+    #[test]
+    fn parse_missing_value() {
+        let mut name = None::<String>;
+        let result = super::Compose::new()
+            .args(args(&["--name"]))
+            .add(ValueOption::new(&mut name, "name").long_arg("name"))
+            .parse();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingValue);
+    }
+
+    #[test]
+    fn parse_short_switch_cluster() {
+        let (mut a, mut b, mut c) = (None::<bool>, None::<bool>, None::<bool>);
+        let result = super::Compose::new()
+            .args(vec!["-abc".to_string()].into_iter())
+            .add(ValueOption::new(&mut a, "a").short_arg('a'))
+            .add(ValueOption::new(&mut b, "b").short_arg('b'))
+            .add(ValueOption::new(&mut c, "c").short_arg('c'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!((a, b, c), (Some(true), Some(true), Some(true)));
+    }
+
+    #[test]
+    fn parse_short_cluster_with_value() {
+        let (mut verbose, mut name) = (None::<bool>, None::<String>);
+        let result = super::Compose::new()
+            .args(vec!["-vn".to_string(), "alice".to_string()].into_iter())
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .add(ValueOption::new(&mut name, "name").short_arg('n'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(verbose, Some(true));
+        assert_eq!(name, Some("alice".to_string()));
+    }
+
+    #[test]
+    fn parse_short_cluster_with_attached_value() {
+        let (mut verbose, mut name) = (None::<bool>, None::<String>);
+        let result = super::Compose::new()
+            .args(vec!["-vnalice".to_string()].into_iter())
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .add(ValueOption::new(&mut name, "name").short_arg('n'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(verbose, Some(true));
+        assert_eq!(name, Some("alice".to_string()));
+    }
+
+    #[test]
+    fn parse_short_attached_value_not_expanded() {
+        let (mut verbose, mut name) = (None::<bool>, None::<String>);
+        let result = super::Compose::new()
+            .args(vec!["-nvalice".to_string()].into_iter())
+            .add(ValueOption::new(&mut verbose, "verbose").short_arg('v'))
+            .add(ValueOption::new(&mut name, "name").short_arg('n'))
+            .parse();
+        assert!(result.is_ok());
+        assert_eq!(verbose, None);
+        assert_eq!(name, Some("valice".to_string()));
+    }
+
     #[test]
     fn parse_propagate_error() {
         let args = vec!["--test".to_string(), "test_value".to_string()];
@@ -144,7 +2119,7 @@ mod tests {
         compose = compose.args(args.clone().into_iter());
         compose = compose.add(MockOption {
             expected_env: HashMap::new(),
-            expected_args: args.clone(),
+            expected_arg: (Some("test_value".to_string()), 1),
             eval_result: Err(Error::Validation("test".to_string())),
         });
 
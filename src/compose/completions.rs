@@ -0,0 +1,525 @@
+use super::CommandInfo;
+use crate::options::{OptionInfo, PositionalArity};
+
+/// The environment variable set by the completion scripts when they call
+/// the program for the candidates of a value, see [`OptionInfo::dynamic_completion`].
+pub(crate) const COMPLETE_ENV: &str = "ROPTS_COMPLETE";
+
+/// The shells a completion script can be generated for,
+/// see [`Compose::generate_completions`](super::Compose::generate_completions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// A command of the tree with the options it accepts.
+struct Entry<'a> {
+    /// The names of the subcommands leading to this command.
+    path: Vec<&'a str>,
+    /// The options of the command itself.
+    own: Vec<&'a OptionInfo>,
+    /// The flag options inherited from the parent commands.
+    inherited: Vec<&'a OptionInfo>,
+    subcommands: &'a [CommandInfo],
+}
+
+impl Entry<'_> {
+    fn options(&self) -> impl Iterator<Item = &OptionInfo> {
+        self.own.iter().chain(&self.inherited).copied()
+    }
+
+    fn flags(&self) -> impl Iterator<Item = &OptionInfo> {
+        self.options().filter(|info| info.positional.is_none())
+    }
+
+    /// Returns the name of the shell function or variable of this command,
+    /// e.g. `demo__db` for the subcommand `db` of `demo`.
+    fn identifier(&self, bin_name: &str) -> String {
+        std::iter::once(bin_name)
+            .chain(self.path.iter().copied())
+            .map(identifier)
+            .collect::<Vec<_>>()
+            .join("__")
+    }
+}
+
+pub(crate) fn generate(shell: Shell, command: &CommandInfo, bin_name: &str) -> String {
+    let mut entries = Vec::new();
+    collect(command, Vec::new(), &[], &mut entries);
+    match shell {
+        Shell::Bash => bash(&entries, bin_name),
+        Shell::Zsh => zsh(&entries, bin_name),
+        Shell::Fish => fish(&entries, bin_name),
+    }
+}
+
+/// Flattens the command tree. The flag options of a command are also accepted
+/// by its subcommands unless a subcommand declares the same flag.
+fn collect<'a>(
+    command: &'a CommandInfo,
+    path: Vec<&'a str>,
+    inherited: &[&'a OptionInfo],
+    entries: &mut Vec<Entry<'a>>,
+) {
+    let own: Vec<&OptionInfo> = command
+        .options
+        .iter()
+        .filter(|info| {
+            info.short_arg.is_some() || info.long_arg.is_some() || info.positional.is_some()
+        })
+        .collect();
+    let inherited: Vec<&OptionInfo> = inherited
+        .iter()
+        .filter(|parent| {
+            !own.iter().any(|info| {
+                (parent.short_arg.is_some() && info.short_arg == parent.short_arg)
+                    || (parent.long_arg.is_some() && info.long_arg == parent.long_arg)
+            })
+        })
+        .copied()
+        .collect();
+    let entry = Entry {
+        path,
+        own,
+        inherited,
+        subcommands: &command.subcommands,
+    };
+    let flags: Vec<&OptionInfo> = entry
+        .own
+        .iter()
+        .chain(&entry.inherited)
+        .filter(|info| info.positional.is_none())
+        .copied()
+        .collect();
+    let path = entry.path.clone();
+    entries.push(entry);
+
+    for subcommand in &command.subcommands {
+        let mut path = path.clone();
+        path.push(&subcommand.name);
+        collect(subcommand, path, &flags, entries);
+    }
+}
+
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn flag_names(info: &OptionInfo) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(short_arg) = info.short_arg {
+        names.push(format!("-{}", short_arg));
+    }
+    if let Some(long_arg) = &info.long_arg {
+        names.push(format!("--{}", long_arg));
+    }
+    names
+}
+
+fn bash(entries: &[Entry], bin_name: &str) -> String {
+    let function = identifier(bin_name);
+    let mut script = format!(
+        "_{function}() {{\n    \
+         local cur prev words cmd opts i\n    \
+         COMPREPLY=()\n    \
+         cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    \
+         prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    \
+         words=(\"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\")\n    \
+         # `=` is a word of its own, `--flag=value` is completed like `--flag value`\n    \
+         if [[ \"${{cur}}\" == \"=\" ]]; then\n        \
+         cur=\"\"\n    \
+         elif [[ \"${{prev}}\" == \"=\" ]]; then\n        \
+         prev=\"${{COMP_WORDS[COMP_CWORD-2]}}\"\n        \
+         words=(\"${{COMP_WORDS[@]:1:COMP_CWORD-2}}\")\n    \
+         fi\n    \
+         cmd=\"{function}\"\n"
+    );
+
+    if entries.iter().any(|entry| !entry.subcommands.is_empty()) {
+        script.push_str(
+            "\n    for ((i = 1; i < COMP_CWORD; i++)); do\n        \
+             case \"${cmd},${COMP_WORDS[i]}\" in\n",
+        );
+        for entry in entries {
+            let parent = entry.identifier(bin_name);
+            for subcommand in entry.subcommands {
+                script.push_str(&format!(
+                    "            {},{})\n                cmd=\"{}__{}\"\n                ;;\n",
+                    parent,
+                    subcommand.name,
+                    parent,
+                    identifier(&subcommand.name)
+                ));
+            }
+        }
+        script.push_str("        esac\n    done\n");
+    }
+
+    script.push_str("\n    case \"${cmd}\" in\n");
+    for entry in entries {
+        let words: Vec<String> = entry
+            .flags()
+            .flat_map(flag_names)
+            .chain(
+                entry
+                    .subcommands
+                    .iter()
+                    .map(|subcommand| subcommand.name.clone()),
+            )
+            .collect();
+        script.push_str(&format!(
+            "        {})\n            opts=\"{}\"\n",
+            entry.identifier(bin_name),
+            words.join(" ")
+        ));
+        let values: Vec<&OptionInfo> = entry
+            .flags()
+            .filter(|info| info.value_name.is_some())
+            .collect();
+        if !values.is_empty() {
+            script.push_str("            case \"${prev}\" in\n");
+            for info in values {
+                let candidates = match (info.dynamic_completion, info.choices.is_empty()) {
+                    (true, _) => format!(
+                        "{}=bash {} -- \"${{words[@]}}\" \"${{cur}}\"",
+                        COMPLETE_ENV, bin_name
+                    ),
+                    (false, false) => {
+                        format!("compgen -W \"{}\" -- \"${{cur}}\"", info.choices.join(" "))
+                    }
+                    (false, true) => "compgen -f -- \"${cur}\"".into(),
+                };
+                script.push_str(&format!(
+                    "                {})\n                    \
+                     COMPREPLY=($({}))\n                    \
+                     return 0\n                    \
+                     ;;\n",
+                    flag_names(info).join("|"),
+                    candidates
+                ));
+            }
+            script.push_str("            esac\n");
+        }
+        script.push_str("            ;;\n");
+    }
+    script.push_str(&format!(
+        "    esac\n\n    \
+         COMPREPLY=($(compgen -W \"${{opts}}\" -- \"${{cur}}\"))\n    \
+         return 0\n\
+         }}\n\n\
+         complete -F _{} -o bashdefault -o default {}\n",
+        function, bin_name
+    ));
+    script
+}
+
+/// Quotes `text` for zsh, escaping the brackets of `_arguments` descriptions.
+fn zsh_description(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn zsh_option(info: &OptionInfo, complete_fn: &str) -> String {
+    let names = flag_names(info);
+    let mut spec = match names.as_slice() {
+        [name] => format!("'{}", name),
+        _ => format!("'({})'{{{}}}'", names.join(" "), names.join(",")),
+    };
+    spec.push_str(&format!("[{}]", zsh_description(&info.description)));
+    if let Some(value_name) = &info.value_name {
+        spec.push_str(&format!(
+            ":{}:{}",
+            zsh_description(value_name).replace(':', "\\:"),
+            zsh_action(info, complete_fn)
+        ));
+    }
+    spec.push('\'');
+    spec
+}
+
+/// Returns how the value of an option is completed, e.g. `(json text)` for choices.
+fn zsh_action(info: &OptionInfo, complete_fn: &str) -> String {
+    match (info.dynamic_completion, info.choices.is_empty()) {
+        (true, _) => complete_fn.to_string(),
+        (false, false) => format!("({})", zsh_description(&info.choices.join(" "))),
+        (false, true) => "_default".to_string(),
+    }
+}
+
+fn zsh_positional(info: &OptionInfo) -> Option<String> {
+    let (name, arity) = info.positional.as_ref()?;
+    let prefix = match (arity, info.required) {
+        (PositionalArity::Single, true) => "",
+        (PositionalArity::Single, false) => ":",
+        (PositionalArity::Variadic, _) => "*",
+    };
+    Some(format!(
+        "'{}:{}:{}'",
+        prefix,
+        zsh_description(name).replace(':', "\\:"),
+        zsh_action(info, "_default")
+    ))
+}
+
+fn zsh(entries: &[Entry], bin_name: &str) -> String {
+    let mut script = format!("#compdef {}\n", bin_name);
+    let root = identifier(bin_name);
+    let complete_fn = format!("_{}_complete", root);
+    let dynamic = entries
+        .iter()
+        .any(|entry| entry.options().any(|info| info.dynamic_completion));
+    if dynamic {
+        // The subcommand functions only see their own words, so the root keeps all of them.
+        script.push_str(&format!(
+            "\n{}() {{\n    \
+             local -a candidates\n    \
+             candidates=(${{(f)\"$({}=zsh {} -- \"${{(@)_{}_words[2,-1]}}\")\"}})\n    \
+             compadd -a candidates\n\
+             }}\n",
+            complete_fn, COMPLETE_ENV, bin_name, root
+        ));
+    }
+    for entry in entries {
+        let function = entry.identifier(bin_name);
+        let mut specs: Vec<String> = entry
+            .flags()
+            .map(|info| zsh_option(info, &complete_fn))
+            .collect();
+        if entry.subcommands.is_empty() {
+            specs.extend(entry.options().filter_map(zsh_positional));
+        } else {
+            specs.push(format!("': :_{}_commands'", function));
+            specs.push("'*::arg:->args'".into());
+        }
+        script.push_str(&format!("\n_{}() {{\n    local line state\n", function));
+        if dynamic && entry.path.is_empty() {
+            script.push_str(&format!(
+                "    local -a _{root}_words\n    _{root}_words=(\"${{(@)words[1,CURRENT]}}\")\n"
+            ));
+        }
+        script.push_str(&format!(
+            "\n    _arguments -C \\\n        {}\n",
+            specs.join(" \\\n        ")
+        ));
+        if entry.subcommands.is_empty() {
+            script.push_str("}\n");
+            continue;
+        }
+
+        script.push_str("\n    case $state in\n        args)\n            case $line[1] in\n");
+        for subcommand in entry.subcommands {
+            script.push_str(&format!(
+                "                {})\n                    _{}__{}\n                    ;;\n",
+                subcommand.name,
+                function,
+                identifier(&subcommand.name)
+            ));
+        }
+        script.push_str("            esac\n            ;;\n    esac\n}\n");
+
+        script.push_str(&format!(
+            "\n_{}_commands() {{\n    local commands\n    commands=(\n",
+            function
+        ));
+        for subcommand in entry.subcommands {
+            let mut line = subcommand.name.replace('\'', "'\\''").replace(':', "\\:");
+            if let Some(about) = &subcommand.about {
+                line.push_str(&format!(":{}", about.replace('\'', "'\\''")));
+            }
+            script.push_str(&format!("        '{}'\n", line));
+        }
+        script.push_str("    )\n    _describe 'command' commands\n}\n");
+    }
+    script.push_str(&format!("\n_{} \"$@\"\n", identifier(bin_name)));
+    script
+}
+
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish(entries: &[Entry], bin_name: &str) -> String {
+    let mut script = String::new();
+    for entry in entries {
+        // Inherited options are already completed by the parent command.
+        let condition = entry.path.last().map(|name| {
+            format!(
+                " -n {}",
+                fish_quote(&format!("__fish_seen_subcommand_from {}", name))
+            )
+        });
+        for info in entry.own.iter().filter(|info| info.positional.is_none()) {
+            let mut line = format!("complete -c {}", bin_name);
+            if let Some(condition) = &condition {
+                line.push_str(condition);
+            }
+            if let Some(short_arg) = info.short_arg {
+                line.push_str(&format!(" -s {}", short_arg));
+            }
+            if let Some(long_arg) = &info.long_arg {
+                line.push_str(&format!(" -l {}", long_arg));
+            }
+            if !info.description.is_empty() {
+                line.push_str(&format!(" -d {}", fish_quote(&info.description)));
+            }
+            if info.value_name.is_some() {
+                line.push_str(" -r");
+            }
+            if info.dynamic_completion {
+                line.push_str(&format!(
+                    " -f -a {}",
+                    fish_quote(&format!(
+                        "({}=fish {} -- (commandline -opc)[2..-1] (commandline -ct))",
+                        COMPLETE_ENV, bin_name
+                    ))
+                ));
+            } else if !info.choices.is_empty() {
+                line.push_str(&format!(" -f -a {}", fish_quote(&info.choices.join(" "))));
+            }
+            script.push_str(&format!("{}\n", line));
+        }
+
+        let names: Vec<&str> = entry
+            .subcommands
+            .iter()
+            .map(|subcommand| subcommand.name.as_str())
+            .collect();
+        let condition = match entry.path.last() {
+            None => "__fish_use_subcommand".to_string(),
+            Some(name) => format!(
+                "__fish_seen_subcommand_from {}; and not __fish_seen_subcommand_from {}",
+                name,
+                names.join(" ")
+            ),
+        };
+        for subcommand in entry.subcommands {
+            let mut line = format!(
+                "complete -c {} -n {} -f -a {}",
+                bin_name,
+                fish_quote(&condition),
+                fish_quote(&subcommand.name)
+            );
+            if let Some(about) = &subcommand.about {
+                line.push_str(&format!(" -d {}", fish_quote(about)));
+            }
+            script.push_str(&format!("{}\n", line));
+        }
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, Shell};
+    use crate::compose::CommandInfo;
+    use crate::options::{OptionInfo, PositionalArity};
+
+    fn command() -> CommandInfo {
+        CommandInfo {
+            name: "demo".into(),
+            about: Some("A demo".into()),
+            options: vec![
+                OptionInfo {
+                    description: "Your name".into(),
+                    env_key: Some("DEMO_NAME".into()),
+                    short_arg: Some('n'),
+                    long_arg: Some("name".into()),
+                    required: true,
+                    value_name: Some("NAME".into()),
+                    ..Default::default()
+                },
+                OptionInfo {
+                    description: "Don't ask".into(),
+                    short_arg: Some('f'),
+                    is_switch: true,
+                    ..Default::default()
+                },
+                OptionInfo {
+                    description: "Secret token".into(),
+                    env_key: Some("DEMO_TOKEN".into()),
+                    ..Default::default()
+                },
+                OptionInfo {
+                    description: "Print help".into(),
+                    short_arg: Some('h'),
+                    long_arg: Some("help".into()),
+                    is_switch: true,
+                    ..Default::default()
+                },
+            ],
+            subcommands: vec![
+                CommandInfo {
+                    name: "db".into(),
+                    about: Some("Database [commands]".into()),
+                    options: vec![OptionInfo {
+                        description: "Database url".into(),
+                        long_arg: Some("url".into()),
+                        value_name: Some("URL".into()),
+                        dynamic_completion: true,
+                        ..Default::default()
+                    }],
+                    subcommands: vec![CommandInfo {
+                        name: "migrate".into(),
+                        about: None,
+                        options: vec![OptionInfo {
+                            description: "Migrations".into(),
+                            positional: Some(("files".into(), PositionalArity::Variadic)),
+                            ..Default::default()
+                        }],
+                        subcommands: vec![],
+                    }],
+                },
+                CommandInfo {
+                    name: "serve".into(),
+                    about: Some("Start the server".into()),
+                    options: vec![
+                        OptionInfo {
+                            description: "Force it".into(),
+                            short_arg: Some('f'),
+                            long_arg: Some("force".into()),
+                            is_switch: true,
+                            ..Default::default()
+                        },
+                        OptionInfo {
+                            description: "Log format".into(),
+                            long_arg: Some("format".into()),
+                            value_name: Some("FORMAT".into()),
+                            choices: vec!["json".into(), "text".into()],
+                            ..Default::default()
+                        },
+                    ],
+                    subcommands: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn bash() {
+        assert_eq!(
+            generate(Shell::Bash, &command(), "demo"),
+            include_str!("snapshots/demo.bash")
+        );
+    }
+
+    #[test]
+    fn zsh() {
+        assert_eq!(
+            generate(Shell::Zsh, &command(), "demo"),
+            include_str!("snapshots/_demo")
+        );
+    }
+
+    #[test]
+    fn fish() {
+        assert_eq!(
+            generate(Shell::Fish, &command(), "demo"),
+            include_str!("snapshots/demo.fish")
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::options::{positional_usage, OptionInfo};
+
+/// Width used if neither an explicit width nor `COLUMNS` is set.
+pub(crate) const DEFAULT_WIDTH: usize = 80;
+/// Descriptions are never wrapped narrower than this.
+const MIN_DESCRIPTION_WIDTH: usize = 20;
+const INDENT: &str = "  ";
+const GAP: &str = "  ";
+
+/// A titled list of entries, e.g. `Options:` followed by one line per option.
+pub(crate) struct Section {
+    pub heading: String,
+    /// The left column and the description of each entry.
+    pub entries: Vec<(String, String)>,
+}
+
+/// Returns the left column and the description of an option,
+/// e.g. `-n, --name <NAME>` and `Your name [env: NAME] [required]`.
+pub(crate) fn option_entry(info: &OptionInfo) -> (String, String) {
+    let mut left = match (&info.positional, info.short_arg, &info.long_arg) {
+        (Some((name, arity)), _, _) => positional_usage(name, *arity, info.required),
+        (None, Some(short_arg), Some(long_arg)) => format!("-{}, --{}", short_arg, long_arg),
+        (None, Some(short_arg), None) => format!("-{}", short_arg),
+        (None, None, Some(long_arg)) => format!("    --{}", long_arg),
+        (None, None, None) => format!("${}", info.env_key.as_deref().unwrap_or_default()),
+    };
+    let has_flags = info.short_arg.is_some() || info.long_arg.is_some();
+    if let (true, Some(value_name)) = (has_flags, &info.value_name) {
+        left.push_str(&format!(" <{}>", value_name));
+        if info.multiple {
+            left.push_str(",...");
+        }
+    }
+
+    let mut description = info.description.clone();
+    if let (true, Some(env_key)) = (has_flags || info.positional.is_some(), &info.env_key) {
+        description.push_str(&format!(" [env: {}]", env_key));
+    }
+    if !info.choices.is_empty() {
+        description.push_str(&format!(" [possible values: {}]", info.choices.join(", ")));
+    }
+    if let Some(default) = &info.default {
+        description.push_str(&format!(" [default: {}]", default));
+    }
+    if info.required {
+        description.push_str(" [required]");
+    }
+
+    (left, description.trim_start().to_string())
+}
+
+/// Renders the sections with the descriptions of all sections aligned in one column
+/// and wrapped to `width`. Empty sections are skipped.
+pub(crate) fn render_sections(sections: &[Section], width: usize) -> String {
+    let left_width = sections
+        .iter()
+        .flat_map(|section| &section.entries)
+        .map(|(left, _)| left.chars().count())
+        .max()
+        .unwrap_or_default();
+    let indent = INDENT.len() + left_width + GAP.len();
+    let description_width = width.saturating_sub(indent).max(MIN_DESCRIPTION_WIDTH);
+
+    let mut rendered = Vec::new();
+    for section in sections
+        .iter()
+        .filter(|section| !section.entries.is_empty())
+    {
+        let mut text = format!("{}:\n", section.heading);
+        for (left, description) in &section.entries {
+            let mut lines = wrap(description, description_width).into_iter();
+            let first = lines.next().unwrap_or_default();
+            let line = format!("{}{:<left_width$}{}{}", INDENT, left, GAP, first);
+            text.push_str(line.trim_end());
+            text.push('\n');
+            for line in lines {
+                text.push_str(&format!("{:indent$}{}\n", "", line));
+            }
+        }
+        rendered.push(text);
+    }
+
+    rendered.join("\n")
+}
+
+/// Splits `text` into lines of at most `width` characters at whitespace.
+/// Bracketed suffixes like `[default: 1]` are not split and
+/// words longer than `width` are kept on their own line.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut bracketed = false;
+    for word in text.split_whitespace() {
+        match words.last_mut() {
+            Some(last) if bracketed => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ => words.push(word.to_string()),
+        }
+        if word.starts_with('[') {
+            bracketed = true;
+        }
+        if word.ends_with(']') {
+            bracketed = false;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in &words {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{option_entry, render_sections, wrap, Section};
+    use crate::options::{OptionInfo, PositionalArity};
+
+    fn entry(left: &str, description: &str) -> (String, String) {
+        (left.to_string(), description.to_string())
+    }
+
+    #[test]
+    fn option_entry_flags() {
+        let info = OptionInfo {
+            description: "Your name".into(),
+            env_key: Some("NAME".into()),
+            short_arg: Some('n'),
+            long_arg: Some("name".into()),
+            required: true,
+            value_name: Some("NAME".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            option_entry(&info),
+            entry("-n, --name <NAME>", "Your name [env: NAME] [required]")
+        );
+
+        let info = OptionInfo {
+            description: "Your skills".into(),
+            short_arg: Some('s'),
+            long_arg: Some("skills".into()),
+            value_name: Some("SKILL".into()),
+            multiple: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            option_entry(&info),
+            entry("-s, --skills <SKILL>,...", "Your skills")
+        );
+
+        let info = OptionInfo {
+            description: "Your age".into(),
+            long_arg: Some("age".into()),
+            default: Some("18".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            option_entry(&info),
+            entry("    --age", "Your age [default: 18]")
+        );
+
+        let info = OptionInfo {
+            description: "Verbose".into(),
+            short_arg: Some('v'),
+            ..Default::default()
+        };
+        assert_eq!(option_entry(&info), entry("-v", "Verbose"));
+
+        let info = OptionInfo {
+            description: "Output format".into(),
+            long_arg: Some("format".into()),
+            default: Some("text".into()),
+            value_name: Some("FORMAT".into()),
+            choices: vec!["json".into(), "text".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            option_entry(&info),
+            entry(
+                "    --format <FORMAT>",
+                "Output format [possible values: json, text] [default: text]"
+            )
+        );
+    }
+
+    #[test]
+    fn option_entry_env_only() {
+        let info = OptionInfo {
+            description: "Token".into(),
+            env_key: Some("TOKEN".into()),
+            ..Default::default()
+        };
+        assert_eq!(option_entry(&info), entry("$TOKEN", "Token"));
+    }
+
+    #[test]
+    fn option_entry_positional() {
+        let info = OptionInfo {
+            description: "Files".into(),
+            env_key: Some("FILES".into()),
+            positional: Some(("files".into(), PositionalArity::Variadic)),
+            ..Default::default()
+        };
+        assert_eq!(
+            option_entry(&info),
+            entry("[files...]", "Files [env: FILES]")
+        );
+    }
+
+    #[test]
+    fn wrap_text() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("unbreakable word", 5), vec!["unbreakable", "word"]);
+        assert_eq!(
+            wrap("Your age [env: AGE] [default: 18]", 16),
+            vec!["Your age", "[env: AGE]", "[default: 18]"]
+        );
+        assert!(wrap("", 10).is_empty());
+    }
+
+    #[test]
+    fn render_aligned_and_wrapped() {
+        let sections = [
+            Section {
+                heading: "Arguments".into(),
+                entries: vec![entry("<file>", "The file")],
+            },
+            Section {
+                heading: "Empty".into(),
+                entries: vec![],
+            },
+            Section {
+                heading: "Options".into(),
+                entries: vec![
+                    entry("-n, --name", "Your name which is used to greet you"),
+                    entry("-v", ""),
+                ],
+            },
+        ];
+        assert_eq!(
+            render_sections(&sections, 40),
+            "Arguments:\n  \
+             <file>      The file\n\
+             \n\
+             Options:\n  \
+             -n, --name  Your name which is used to\n              \
+             greet you\n  \
+             -v\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use super::help::option_entry;
+use crate::options::OptionInfo;
+
+/// Escapes `text` for roff, e.g. `-` as `\-` so it is not rendered as a hyphen.
+/// Empty lines start a new paragraph.
+pub(crate) fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            match line.as_str() {
+                "" => ".PP".to_string(),
+                _ if line.starts_with(['.', '\'']) => format!("\\&{}", line),
+                _ => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the tagged paragraph of an option, e.g. `\fB\-n\fR, \fB\-\-name\fR \fINAME\fR`
+/// followed by the description with the same suffixes as in the help.
+/// Options without flags are only listed in the ENVIRONMENT section.
+pub(crate) fn option_item(info: &OptionInfo) -> Option<String> {
+    let (left, description) = option_entry(info);
+    let tag = match (&info.positional, info.short_arg, &info.long_arg) {
+        (Some(_), _, _) => format!("\\fI{}\\fR", escape(&left)),
+        (None, None, None) => return None,
+        (None, short_arg, long_arg) => {
+            let mut flags: Vec<String> = Vec::new();
+            if let Some(short_arg) = short_arg {
+                flags.push(format!("\\fB\\-{}\\fR", escape(&short_arg.to_string())));
+            }
+            if let Some(long_arg) = long_arg {
+                flags.push(format!("\\fB\\-\\-{}\\fR", escape(long_arg)));
+            }
+            let mut tag = flags.join(", ");
+            if let Some(value_name) = &info.value_name {
+                tag.push_str(&format!(" \\fI{}\\fR", escape(value_name)));
+                if info.multiple {
+                    tag.push_str(",...");
+                }
+            }
+            tag
+        }
+    };
+    Some(paragraph(&tag, &description))
+}
+
+/// Returns the tagged paragraph of the environment variable of an option.
+pub(crate) fn environment_item(info: &OptionInfo) -> Option<String> {
+    let env_key = info.env_key.as_ref()?;
+    Some(paragraph(
+        &format!("\\fB{}\\fR", escape(env_key)),
+        &info.description,
+    ))
+}
+
+pub(crate) fn paragraph(tag: &str, text: &str) -> String {
+    match text.is_empty() {
+        true => format!(".TP\n{}\n", tag),
+        false => format!(".TP\n{}\n{}\n", tag, escape(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{environment_item, escape, option_item};
+    use crate::options::{OptionInfo, PositionalArity};
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a-b \\ c"), "a\\-b \\e c");
+        assert_eq!(
+            escape(".hidden\n\n'quoted'"),
+            "\\&.hidden\n.PP\n\\&'quoted'"
+        );
+    }
+
+    #[test]
+    fn option_items() {
+        let info = OptionInfo {
+            description: "Your skills".into(),
+            env_key: Some("SKILLS".into()),
+            short_arg: Some('s'),
+            long_arg: Some("skills".into()),
+            value_name: Some("SKILL".into()),
+            multiple: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            option_item(&info).unwrap(),
+            ".TP\n\\fB\\-s\\fR, \\fB\\-\\-skills\\fR \\fISKILL\\fR,...\nYour skills [env: SKILLS]\n"
+        );
+        assert_eq!(
+            environment_item(&info).unwrap(),
+            ".TP\n\\fBSKILLS\\fR\nYour skills\n"
+        );
+
+        let info = OptionInfo {
+            positional: Some(("file".into(), PositionalArity::Single)),
+            required: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            option_item(&info).unwrap(),
+            ".TP\n\\fI<file>\\fR\n[required]\n"
+        );
+        assert_eq!(environment_item(&info), None);
+
+        let info = OptionInfo {
+            description: "Token".into(),
+            env_key: Some("TOKEN".into()),
+            ..Default::default()
+        };
+        assert_eq!(option_item(&info), None);
+    }
+}
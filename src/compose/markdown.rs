@@ -0,0 +1,150 @@
+use super::help::option_entry;
+use super::CommandInfo;
+use crate::options::OptionInfo;
+
+/// Escapes `text` for a table cell, e.g. `|` as `\|`.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn code(text: &str) -> String {
+    match text.is_empty() {
+        true => String::new(),
+        false => format!("`{}`", cell(text)),
+    }
+}
+
+/// Returns a table with one row per option, e.g.
+/// ``| `-n, --name <NAME>` | `NAME` | `String` | | yes | Your name |``.
+pub(crate) fn options_table(options: &[OptionInfo]) -> String {
+    let mut table = "| Flags | Env var | Type | Default | Required | Description |\n\
+                     | --- | --- | --- | --- | --- | --- |\n"
+        .to_string();
+    for info in options {
+        let flags = match info.short_arg.is_some()
+            || info.long_arg.is_some()
+            || info.positional.is_some()
+        {
+            true => option_entry(info).0.trim().to_string(),
+            false => String::new(),
+        };
+        let value_type = match (info.choices.is_empty(), info.multiple) {
+            (false, _) => info.choices.join(" | "),
+            (true, true) => format!("Vec<{}>", info.value_type),
+            (true, false) => info.value_type.clone(),
+        };
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            code(&flags),
+            code(info.env_key.as_deref().unwrap_or_default()),
+            code(&value_type),
+            code(info.default.as_deref().unwrap_or_default()),
+            if info.required { "yes" } else { "no" },
+            cell(&info.description)
+        ));
+    }
+    table
+}
+
+/// Renders a section per subcommand, headed by the full command line, e.g. `demo db migrate`.
+pub(crate) fn subcommand_sections(command: &CommandInfo, path: &str) -> String {
+    let mut sections = String::new();
+    for subcommand in &command.subcommands {
+        let path = format!("{} {}", path, subcommand.name);
+        sections.push_str(&format!("\n## {}\n", path));
+        if let Some(about) = &subcommand.about {
+            sections.push_str(&format!("\n{}\n", about));
+        }
+        if !subcommand.options.is_empty() {
+            sections.push_str(&format!("\n{}", options_table(&subcommand.options)));
+        }
+        sections.push_str(&subcommand_sections(subcommand, &path));
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{options_table, subcommand_sections};
+    use crate::compose::CommandInfo;
+    use crate::options::{OptionInfo, PositionalArity};
+
+    #[test]
+    fn table() {
+        let options = [
+            OptionInfo {
+                description: "Your skills | talents".into(),
+                env_key: Some("SKILLS".into()),
+                short_arg: Some('s'),
+                long_arg: Some("skills".into()),
+                value_name: Some("SKILL".into()),
+                value_type: "String".into(),
+                multiple: true,
+                ..Default::default()
+            },
+            OptionInfo {
+                description: "Your age".into(),
+                long_arg: Some("age".into()),
+                required: true,
+                default: Some("18".into()),
+                value_name: Some("YEARS".into()),
+                value_type: "u8".into(),
+                ..Default::default()
+            },
+            OptionInfo {
+                description: "Format".into(),
+                long_arg: Some("format".into()),
+                value_name: Some("FORMAT".into()),
+                value_type: "Format".into(),
+                choices: vec!["json".into(), "text".into()],
+                ..Default::default()
+            },
+            OptionInfo {
+                description: "Token".into(),
+                env_key: Some("TOKEN".into()),
+                value_type: "String".into(),
+                ..Default::default()
+            },
+            OptionInfo {
+                description: "Input".into(),
+                positional: Some(("file".into(), PositionalArity::Single)),
+                value_type: "String".into(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            options_table(&options),
+            "| Flags | Env var | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- | --- |\n\
+             | `-s, --skills <SKILL>,...` | `SKILLS` | `Vec<String>` |  | no | Your skills \\| talents |\n\
+             | `--age <YEARS>` |  | `u8` | `18` | yes | Your age |\n\
+             | `--format <FORMAT>` |  | `json \\| text` |  | no | Format |\n\
+             |  | `TOKEN` | `String` |  | no | Token |\n\
+             | `[file]` |  | `String` |  | no | Input |\n"
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let command = CommandInfo {
+            name: "demo".into(),
+            about: None,
+            options: vec![],
+            subcommands: vec![CommandInfo {
+                name: "db".into(),
+                about: Some("Database commands".into()),
+                options: vec![],
+                subcommands: vec![CommandInfo {
+                    name: "migrate".into(),
+                    about: None,
+                    options: vec![],
+                    subcommands: vec![],
+                }],
+            }],
+        };
+        assert_eq!(
+            subcommand_sections(&command, "demo"),
+            "\n## demo db\n\nDatabase commands\n\n## demo db migrate\n"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+_demo() {
+    local cur prev words cmd opts i
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    words=("${COMP_WORDS[@]:1:COMP_CWORD-1}")
+    # `=` is a word of its own, `--flag=value` is completed like `--flag value`
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+        words=("${COMP_WORDS[@]:1:COMP_CWORD-2}")
+    fi
+    cmd="demo"
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd},${COMP_WORDS[i]}" in
+            demo,db)
+                cmd="demo__db"
+                ;;
+            demo,serve)
+                cmd="demo__serve"
+                ;;
+            demo__db,migrate)
+                cmd="demo__db__migrate"
+                ;;
+        esac
+    done
+
+    case "${cmd}" in
+        demo)
+            opts="-n --name -f -h --help db serve"
+            case "${prev}" in
+                -n|--name)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+            esac
+            ;;
+        demo__db)
+            opts="--url -n --name -f -h --help migrate"
+            case "${prev}" in
+                --url)
+                    COMPREPLY=($(ROPTS_COMPLETE=bash demo -- "${words[@]}" "${cur}"))
+                    return 0
+                    ;;
+                -n|--name)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+            esac
+            ;;
+        demo__db__migrate)
+            opts="--url -n --name -f -h --help"
+            case "${prev}" in
+                --url)
+                    COMPREPLY=($(ROPTS_COMPLETE=bash demo -- "${words[@]}" "${cur}"))
+                    return 0
+                    ;;
+                -n|--name)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+            esac
+            ;;
+        demo__serve)
+            opts="-f --force --format -n --name -h --help"
+            case "${prev}" in
+                --format)
+                    COMPREPLY=($(compgen -W "json text" -- "${cur}"))
+                    return 0
+                    ;;
+                -n|--name)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+            esac
+            ;;
+    esac
+
+    COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+    return 0
+}
+
+complete -F _demo -o bashdefault -o default demo
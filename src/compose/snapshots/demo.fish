@@ -0,0 +1,9 @@
+complete -c demo -s n -l name -d 'Your name' -r
+complete -c demo -s f -d 'Don\'t ask'
+complete -c demo -s h -l help -d 'Print help'
+complete -c demo -n '__fish_use_subcommand' -f -a 'db' -d 'Database [commands]'
+complete -c demo -n '__fish_use_subcommand' -f -a 'serve' -d 'Start the server'
+complete -c demo -n '__fish_seen_subcommand_from db' -l url -d 'Database url' -r -f -a '(ROPTS_COMPLETE=fish demo -- (commandline -opc)[2..-1] (commandline -ct))'
+complete -c demo -n '__fish_seen_subcommand_from db; and not __fish_seen_subcommand_from migrate' -f -a 'migrate'
+complete -c demo -n '__fish_seen_subcommand_from serve' -s f -l force -d 'Force it'
+complete -c demo -n '__fish_seen_subcommand_from serve' -l format -d 'Log format' -r -f -a 'json text'
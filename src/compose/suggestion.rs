@@ -0,0 +1,54 @@
+/// Returns the candidate closest to `input`, if it is close enough to be a likely typo.
+pub(crate) fn did_you_mean<'c>(
+    input: &str,
+    candidates: impl Iterator<Item = &'c str>,
+) -> Option<&'c str> {
+    candidates
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= 2 && distance * 2 < candidate.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{did_you_mean, edit_distance};
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("name", "name"), 0);
+        assert_eq!(edit_distance("name", ""), 4);
+        assert_eq!(edit_distance("nmae", "name"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggestion() {
+        let candidates = ["--name", "--age", "-n", "-a"];
+        assert_eq!(
+            did_you_mean("--nmae", candidates.into_iter()),
+            Some("--name")
+        );
+        assert_eq!(did_you_mean("--ag", candidates.into_iter()), Some("--age"));
+        assert_eq!(did_you_mean("-x", candidates.into_iter()), None);
+        assert_eq!(did_you_mean("--skills", candidates.into_iter()), None);
+    }
+}
@@ -0,0 +1,306 @@
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    /// A long flag like `--name`.
+    Long(String),
+    /// A short flag like `-n`, or a single flag out of a cluster like `-abc`.
+    Short(char),
+    /// A value attached to (`--name=alice`, `-nalice`) or following (`--name alice`) a flag.
+    Value(String),
+    /// An argument that does not belong to any flag.
+    Positional(String),
+    /// The end-of-options terminator `--`.
+    Terminator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token {
+    /// Index of the argument the token was read from.
+    pub position: usize,
+    pub kind: TokenKind,
+}
+
+pub(crate) enum Flag<'f> {
+    Long(&'f str),
+    Short(char),
+}
+
+/// Splits `args` into a stream of tokens.
+///
+/// `lookup` returns `None` for unknown flags, otherwise whether the flag is a switch.
+/// Knowing the flags is required to decide whether `-abc` is a cluster of switches
+/// or `-a` with the attached value `bc`, and whether the next argument is a value.
+pub(crate) fn tokenize(args: &[String], lookup: impl Fn(Flag) -> Option<bool>) -> Vec<Token> {
+    let mut tokens = Vec::with_capacity(args.len());
+    let mut args = args.iter().enumerate();
+
+    while let Some((position, arg)) = args.next() {
+        let mut push = |kind| tokens.push(Token { position, kind });
+
+        let switch = if arg == "--" {
+            // Everything after the terminator is passed through verbatim.
+            push(TokenKind::Terminator);
+            for (position, arg) in args.by_ref() {
+                tokens.push(Token {
+                    position,
+                    kind: TokenKind::Positional(arg.to_string()),
+                });
+            }
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            push(TokenKind::Long(name.to_string()));
+            if let Some(value) = value {
+                push(TokenKind::Value(value.to_string()));
+                continue;
+            }
+            lookup(Flag::Long(name))
+        } else if let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) {
+            let mut switch = None;
+            let mut attached = false;
+            for (offset, flag) in cluster.char_indices() {
+                push(TokenKind::Short(flag));
+                switch = lookup(Flag::Short(flag));
+
+                let rest = &cluster[offset + flag.len_utf8()..];
+                if switch != Some(true) || rest.starts_with('=') {
+                    if !rest.is_empty() {
+                        let value = rest.strip_prefix('=').unwrap_or(rest);
+                        push(TokenKind::Value(value.to_string()));
+                        attached = true;
+                    }
+                    break;
+                }
+            }
+            if attached {
+                continue;
+            }
+            switch
+        } else {
+            push(TokenKind::Positional(arg.to_string()));
+            continue;
+        };
+
+        // The flag has no attached value, so it takes the next argument unless it is a switch.
+        // Switches only take a value in the attached form (e.g. `--verbose=false`).
+        if switch == Some(false) {
+            if let Some((position, value)) = args.next() {
+                tokens.push(Token {
+                    position,
+                    kind: TokenKind::Value(value.to_string()),
+                });
+            }
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Flag, Token, TokenKind};
+
+    fn lookup(flag: Flag) -> Option<bool> {
+        match flag {
+            Flag::Long("name") | Flag::Short('n') => Some(false),
+            Flag::Long("verbose") | Flag::Short('v') | Flag::Short('a') => Some(true),
+            _ => None,
+        }
+    }
+
+    fn kinds(args: &[&str]) -> Vec<TokenKind> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        tokenize(&args, lookup)
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn long_with_value() {
+        assert_eq!(
+            kinds(&["--name", "alice"]),
+            vec![
+                TokenKind::Long("name".into()),
+                TokenKind::Value("alice".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn long_with_flag_like_value() {
+        assert_eq!(
+            kinds(&["--name", "--verbose"]),
+            vec![
+                TokenKind::Long("name".into()),
+                TokenKind::Value("--verbose".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn long_attached() {
+        assert_eq!(
+            kinds(&["--name=alice", "bob"]),
+            vec![
+                TokenKind::Long("name".into()),
+                TokenKind::Value("alice".into()),
+                TokenKind::Positional("bob".into())
+            ]
+        );
+        assert_eq!(
+            kinds(&["--name="]),
+            vec![TokenKind::Long("name".into()), TokenKind::Value("".into())]
+        );
+    }
+
+    #[test]
+    fn long_unknown() {
+        assert_eq!(
+            kinds(&["--other", "bob"]),
+            vec![
+                TokenKind::Long("other".into()),
+                TokenKind::Positional("bob".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn short_with_value() {
+        assert_eq!(
+            kinds(&["-n", "alice"]),
+            vec![TokenKind::Short('n'), TokenKind::Value("alice".into())]
+        );
+    }
+
+    #[test]
+    fn short_attached() {
+        assert_eq!(
+            kinds(&["-nalice"]),
+            vec![TokenKind::Short('n'), TokenKind::Value("alice".into())]
+        );
+        assert_eq!(
+            kinds(&["-n=alice"]),
+            vec![TokenKind::Short('n'), TokenKind::Value("alice".into())]
+        );
+        assert_eq!(
+            kinds(&["-n="]),
+            vec![TokenKind::Short('n'), TokenKind::Value("".into())]
+        );
+    }
+
+    #[test]
+    fn short_cluster() {
+        assert_eq!(
+            kinds(&["-av", "-vn", "alice"]),
+            vec![
+                TokenKind::Short('a'),
+                TokenKind::Short('v'),
+                TokenKind::Short('v'),
+                TokenKind::Short('n'),
+                TokenKind::Value("alice".into())
+            ]
+        );
+        assert_eq!(
+            kinds(&["-vnalice"]),
+            vec![
+                TokenKind::Short('v'),
+                TokenKind::Short('n'),
+                TokenKind::Value("alice".into())
+            ]
+        );
+        assert_eq!(
+            kinds(&["-v=false"]),
+            vec![TokenKind::Short('v'), TokenKind::Value("false".into())]
+        );
+    }
+
+    #[test]
+    fn switch_without_value() {
+        assert_eq!(
+            kinds(&["--verbose", "file.txt", "-v", "true"]),
+            vec![
+                TokenKind::Long("verbose".into()),
+                TokenKind::Positional("file.txt".into()),
+                TokenKind::Short('v'),
+                TokenKind::Positional("true".into())
+            ]
+        );
+        assert_eq!(
+            kinds(&["--verbose=false"]),
+            vec![
+                TokenKind::Long("verbose".into()),
+                TokenKind::Value("false".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn short_unknown() {
+        assert_eq!(
+            kinds(&["-xyz", "bob"]),
+            vec![
+                TokenKind::Short('x'),
+                TokenKind::Value("yz".into()),
+                TokenKind::Positional("bob".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn positional_and_terminator() {
+        assert_eq!(
+            kinds(&["file", "-", "--"]),
+            vec![
+                TokenKind::Positional("file".into()),
+                TokenKind::Positional("-".into()),
+                TokenKind::Terminator
+            ]
+        );
+    }
+
+    #[test]
+    fn terminator() {
+        assert_eq!(
+            kinds(&["-v", "--", "-rf", "--name", "--"]),
+            vec![
+                TokenKind::Short('v'),
+                TokenKind::Terminator,
+                TokenKind::Positional("-rf".into()),
+                TokenKind::Positional("--name".into()),
+                TokenKind::Positional("--".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn positions() {
+        let args: Vec<String> = ["-vn", "alice", "bob"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            tokenize(&args, lookup),
+            vec![
+                Token {
+                    position: 0,
+                    kind: TokenKind::Short('v')
+                },
+                Token {
+                    position: 0,
+                    kind: TokenKind::Short('n')
+                },
+                Token {
+                    position: 1,
+                    kind: TokenKind::Value("alice".into())
+                },
+                Token {
+                    position: 2,
+                    kind: TokenKind::Positional("bob".into())
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use crate::compose::Compose;
+use crate::error::Error;
+use crate::matches::Matches;
+
+pub use crate::options::ValueEnum;
+#[cfg(feature = "derive")]
+pub use ropts_derive::{Ropts, ValueEnum};
+
+/// A struct whose fields are parsed as options.
+/// Usually implemented with `#[derive(Ropts)]` (requires the `derive` feature).
+///
+/// # Examples
+///
+/// ```ignore
+/// use ropts::{compose::Compose, derive::Ropts};
+///
+/// #[derive(Ropts)]
+/// struct Demo {
+///     /// Your name
+///     #[ropts(env = "DEMO_NAME", short = 'n', long, required)]
+///     name: String,
+///     /// Your age
+///     #[ropts(short, long, default = 18)]
+///     age: u8,
+///     /// Your skills
+///     #[ropts(long)]
+///     skills: Option<Vec<String>>,
+/// }
+///
+/// let demo = Demo::parse(Compose::new().args(std::env::args().skip(1)))?;
+/// ```
+pub trait Ropts: Sized {
+    /// Adds an option with the field name as id for every field to `compose`.
+    fn register(compose: Compose<'_>) -> Compose<'_>;
+
+    /// Builds the struct from the result of a compose passed through [`Ropts::register`].
+    fn from_matches(matches: &Matches) -> Result<Self, Error>;
+
+    /// Registers the options on `compose`, parses it and returns the populated struct.
+    fn parse(compose: Compose<'_>) -> Result<Self, Error> {
+        Self::from_matches(&Self::register(compose).parse_matches()?)
+    }
+}
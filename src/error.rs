@@ -1,7 +1,16 @@
 use std::fmt;
 
+/// Exit code for a successful termination, see `sysexits.h`.
+const EX_OK: i32 = 0;
+/// Exit code for command line usage errors, see `sysexits.h`.
+const EX_USAGE: i32 = 64;
+
 /// An error type for validation and parsing errors.
 ///
+/// Errors raised by the parser are [`Error::Detailed`] and carry an [`ErrorKind`],
+/// the identifiers of the offending option, the raw input and its [`Source`].
+/// Use [`Error::kind`] to tell them apart without matching on the message.
+///
 /// # Examples
 ///
 /// ```
@@ -12,11 +21,280 @@ use std::fmt;
 ///
 /// let parsing_error = Error::Parsing("Failed to parse".to_string());
 /// assert_eq!(format!("{}", parsing_error), "Parsing error: Failed to parse");
+///
+/// let multiple_error = Error::Multiple(vec![validation_error, parsing_error]);
+/// assert_eq!(
+///     format!("{}", multiple_error),
+///     "Validation error: Invalid input\nParsing error: Failed to parse"
+/// );
 /// ```
 #[derive(Debug, Clone)]
 pub enum Error {
     Validation(String),
     Parsing(String),
+    /// All errors of a parse run, displayed one per line.
+    Multiple(Vec<Error>),
+    /// The help flag was given, the help has been printed and nothing was parsed.
+    HelpRequested,
+    /// The version flag was given, the version has been printed and nothing was parsed.
+    VersionRequested,
+    /// The program was called by a completion script, the candidates have been printed
+    /// and nothing was parsed.
+    CompletionRequested,
+    /// An error with structured context, displayed like the message variants.
+    Detailed(Box<ErrorDetails>),
+}
+
+/// The category of an [`Error`].
+/// New kinds may be added, matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A required option has no value.
+    MissingRequired,
+    /// The input could not be converted to the type of the option.
+    InvalidValue,
+    /// An option that expects a value was given without one.
+    MissingValue,
+    /// The value was rejected by the `additional_eval` of the option.
+    ValidationFailed,
+    /// An argument does not belong to any option.
+    UnknownArgument,
+    /// The option cannot be used together with the option or argument in `input`.
+    Conflict,
+    /// An [`Error::Validation`] message.
+    Validation,
+    /// An [`Error::Parsing`] message.
+    Parsing,
+    /// An [`Error::Multiple`] collection.
+    Multiple,
+    /// The help flag was given, see [`Error::HelpRequested`].
+    HelpRequested,
+    /// The version flag was given, see [`Error::VersionRequested`].
+    VersionRequested,
+    /// Completion candidates were requested, see [`Error::CompletionRequested`].
+    CompletionRequested,
+}
+
+/// Where the value of an option came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// The environment variable with the given key.
+    Env(String),
+    /// The argument at the given 1-based position.
+    Arg(usize),
+    /// The default value of the option.
+    Default,
+}
+
+/// The identifiers of an option, displayed as `{ENV, -s, --long, <name>}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Identifiers {
+    pub env_key: Option<String>,
+    pub short_arg: Option<char>,
+    pub long_arg: Option<String>,
+    pub positional: Option<String>,
+}
+
+/// The context of an [`Error::Detailed`].
+#[derive(Debug, Clone)]
+pub struct ErrorDetails {
+    pub kind: ErrorKind,
+    /// The identifiers of the offending option, `None` for unknown arguments.
+    pub option: Option<Identifiers>,
+    /// The raw input as given on the command line or in the environment.
+    pub input: Option<String>,
+    pub source: Option<Source>,
+    /// The underlying error, e.g. the conversion or `additional_eval` error.
+    pub cause: Option<Error>,
+    /// A similar known flag for unknown arguments.
+    pub suggestion: Option<String>,
+}
+
+impl Error {
+    /// Creates an [`Error::Detailed`] of `kind` without any context.
+    pub fn new(kind: ErrorKind) -> Self {
+        Error::Detailed(Box::new(ErrorDetails {
+            kind,
+            option: None,
+            input: None,
+            source: None,
+            cause: None,
+            suggestion: None,
+        }))
+    }
+
+    pub fn with_option(self, option: Identifiers) -> Self {
+        self.map_details(|details| details.option = Some(option))
+    }
+
+    pub fn with_input(self, input: impl Into<String>) -> Self {
+        self.map_details(|details| details.input = Some(input.into()))
+    }
+
+    pub fn with_source(self, source: Source) -> Self {
+        self.map_details(|details| details.source = Some(source))
+    }
+
+    pub fn with_cause(self, cause: Error) -> Self {
+        self.map_details(|details| details.cause = Some(cause))
+    }
+
+    pub fn with_suggestion(self, suggestion: impl Into<String>) -> Self {
+        self.map_details(|details| details.suggestion = Some(suggestion.into()))
+    }
+
+    fn map_details(mut self, f: impl FnOnce(&mut ErrorDetails)) -> Self {
+        if let Error::Detailed(details) = &mut self {
+            f(details);
+        }
+        self
+    }
+
+    /// Returns the category of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ropts::error::{Error, ErrorKind};
+    ///
+    /// assert_eq!(Error::Validation("Invalid input".into()).kind(), ErrorKind::Validation);
+    /// assert_eq!(Error::new(ErrorKind::MissingRequired).kind(), ErrorKind::MissingRequired);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Validation(_) => ErrorKind::Validation,
+            Error::Parsing(_) => ErrorKind::Parsing,
+            Error::Multiple(_) => ErrorKind::Multiple,
+            Error::HelpRequested => ErrorKind::HelpRequested,
+            Error::VersionRequested => ErrorKind::VersionRequested,
+            Error::CompletionRequested => ErrorKind::CompletionRequested,
+            Error::Detailed(details) => details.kind,
+        }
+    }
+
+    /// Returns the exit code for the error, following the BSD `sysexits.h` convention.
+    /// Requesting the help or version is a success (0), all other errors are caused by the
+    /// command line input and map to 64 (`EX_USAGE`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ropts::error::Error;
+    ///
+    /// assert_eq!(Error::Validation("Invalid input".into()).exit_code(), 64);
+    /// assert_eq!(Error::HelpRequested.exit_code(), 0);
+    /// assert_eq!(Error::VersionRequested.exit_code(), 0);
+    /// assert_eq!(Error::CompletionRequested.exit_code(), 0);
+    /// ```
+    pub fn exit_code(&self) -> i32 {
+        match self.kind() {
+            ErrorKind::HelpRequested
+            | ErrorKind::VersionRequested
+            | ErrorKind::CompletionRequested => EX_OK,
+            _ => EX_USAGE,
+        }
+    }
+
+    /// Prints the error to stderr and exits the process with [`Error::exit_code`].
+    /// Nothing is printed on a successful exit, e.g. after the help was printed.
+    pub fn exit(&self) -> ! {
+        let code = self.exit_code();
+        if code != EX_OK {
+            eprintln!("{}", self);
+        }
+        std::process::exit(code)
+    }
+
+    /// Returns the message of the error without the prefix of its variant, e.g. `Invalid input`
+    /// for `Error::Validation("Invalid input")`. Used to append an error as the cause of another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ropts::error::Error;
+    ///
+    /// assert_eq!(Error::Validation("Invalid input".into()).message(), "Invalid input");
+    /// assert_eq!(Error::HelpRequested.message(), "Help requested");
+    /// ```
+    pub fn message(&self) -> String {
+        match self {
+            Error::Validation(msg) | Error::Parsing(msg) => msg.clone(),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns the context of the error if it is an [`Error::Detailed`].
+    pub fn details(&self) -> Option<&ErrorDetails> {
+        match self {
+            Error::Detailed(details) => Some(details),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Identifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut identifiers = Vec::new();
+        if let Some(env_key) = &self.env_key {
+            identifiers.push(env_key.clone());
+        }
+        if let Some(short_arg) = self.short_arg {
+            identifiers.push(format!("-{}", short_arg));
+        }
+        if let Some(long_arg) = &self.long_arg {
+            identifiers.push(format!("--{}", long_arg));
+        }
+        if let Some(name) = &self.positional {
+            identifiers.push(format!("<{}>", name));
+        }
+        write!(f, "{{{}}}", identifiers.join(", "))
+    }
+}
+
+impl fmt::Display for ErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let option = self.option.clone().unwrap_or_default();
+        let input = self.input.as_deref().unwrap_or_default();
+        match self.kind {
+            ErrorKind::MissingRequired => write!(f, "Validation error: {} is required", option)?,
+            ErrorKind::InvalidValue => {
+                write!(f, "Parsing error: {} failed to parse ", option)?;
+                match &self.source {
+                    Some(Source::Env(env_key)) => write!(f, "environment variable {}", env_key)?,
+                    Some(Source::Arg(position)) => write!(f, "argument at position {}", position)?,
+                    Some(Source::Default) => write!(f, "default value")?,
+                    None => write!(f, "{:?}", input)?,
+                }
+            }
+            ErrorKind::MissingValue => {
+                write!(f, "Parsing error: {} expects a value", option)?;
+                if let Some(Source::Arg(position)) = &self.source {
+                    write!(f, " at position {}", position)?;
+                }
+            }
+            ErrorKind::ValidationFailed => {
+                write!(f, "Validation error: {} failed validation", option)?
+            }
+            ErrorKind::UnknownArgument => write!(f, "Unknown argument: {}", input)?,
+            ErrorKind::Conflict => {
+                write!(f, "Validation error: {} conflicts with {}", option, input)?
+            }
+            ErrorKind::Validation => write!(f, "Validation error: {}", input)?,
+            ErrorKind::Parsing => write!(f, "Parsing error: {}", input)?,
+            ErrorKind::Multiple => write!(f, "Multiple errors")?,
+            ErrorKind::HelpRequested => write!(f, "Help requested")?,
+            ErrorKind::VersionRequested => write!(f, "Version requested")?,
+            ErrorKind::CompletionRequested => write!(f, "Completion requested")?,
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean {}?)", suggestion)?;
+        }
+        if let Some(cause) = &self.cause {
+            write!(f, ": {}", cause.message())?;
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for Error {
@@ -34,6 +312,19 @@ impl fmt::Display for Error {
         match self {
             Error::Validation(msg) => write!(f, "Validation error: {}", msg),
             Error::Parsing(msg) => write!(f, "Parsing error: {}", msg),
+            Error::HelpRequested => write!(f, "Help requested"),
+            Error::VersionRequested => write!(f, "Version requested"),
+            Error::CompletionRequested => write!(f, "Completion requested"),
+            Error::Detailed(details) => write!(f, "{}", details),
+            Error::Multiple(errors) => {
+                for (index, error) in errors.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -55,4 +346,108 @@ mod tests {
         let err = Error::Parsing("test".to_string());
         assert_eq!(format!("{}", err), "Parsing error: test");
     }
+
+    #[test]
+    fn error_kind() {
+        assert_eq!(Error::Parsing("test".into()).kind(), ErrorKind::Parsing);
+        assert_eq!(Error::Multiple(vec![]).kind(), ErrorKind::Multiple);
+        assert_eq!(
+            Error::new(ErrorKind::InvalidValue).kind(),
+            ErrorKind::InvalidValue
+        );
+        assert!(Error::Validation("test".into()).details().is_none());
+    }
+
+    fn identifiers() -> Identifiers {
+        Identifiers {
+            env_key: Some("AGE".into()),
+            short_arg: Some('a'),
+            long_arg: Some("age".into()),
+            positional: None,
+        }
+    }
+
+    #[test]
+    fn identifiers_display() {
+        assert_eq!(identifiers().to_string(), "{AGE, -a, --age}");
+        assert_eq!(Identifiers::default().to_string(), "{}");
+    }
+
+    #[test]
+    fn error_detailed_display() {
+        let cause = Error::Parsing("Error converting from \"abc\" to u8".into());
+        let err = Error::new(ErrorKind::InvalidValue)
+            .with_option(identifiers())
+            .with_input("abc")
+            .with_source(Source::Arg(2))
+            .with_cause(cause.clone());
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: {AGE, -a, --age} failed to parse argument at position 2: \
+             Error converting from \"abc\" to u8"
+        );
+        let details = err.details().unwrap();
+        assert_eq!(details.input.as_deref(), Some("abc"));
+        assert_eq!(details.source, Some(Source::Arg(2)));
+
+        let err = Error::new(ErrorKind::InvalidValue)
+            .with_option(identifiers())
+            .with_source(Source::Env("AGE".into()))
+            .with_cause(cause);
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: {AGE, -a, --age} failed to parse environment variable AGE: \
+             Error converting from \"abc\" to u8"
+        );
+
+        let err = Error::new(ErrorKind::MissingRequired).with_option(identifiers());
+        assert_eq!(
+            err.to_string(),
+            "Validation error: {AGE, -a, --age} is required"
+        );
+
+        let err = Error::new(ErrorKind::MissingValue)
+            .with_option(identifiers())
+            .with_source(Source::Arg(1));
+        assert_eq!(
+            err.to_string(),
+            "Parsing error: {AGE, -a, --age} expects a value at position 1"
+        );
+
+        let err = Error::new(ErrorKind::ValidationFailed)
+            .with_option(identifiers())
+            .with_cause(Error::Validation("too young".into()));
+        assert_eq!(
+            err.to_string(),
+            "Validation error: {AGE, -a, --age} failed validation: too young"
+        );
+
+        let err = Error::new(ErrorKind::UnknownArgument)
+            .with_input("--aeg")
+            .with_suggestion("--age");
+        assert_eq!(
+            err.to_string(),
+            "Unknown argument: --aeg (did you mean --age?)"
+        );
+
+        let err = Error::new(ErrorKind::Conflict)
+            .with_option(identifiers())
+            .with_input("--young");
+        assert_eq!(
+            err.to_string(),
+            "Validation error: {AGE, -a, --age} conflicts with --young"
+        );
+    }
+
+    #[test]
+    fn error_multiple_display() {
+        let err = Error::Multiple(vec![
+            Error::Validation("first".to_string()),
+            Error::Parsing("second".to_string()),
+        ]);
+        assert_eq!(
+            format!("{}", err),
+            "Validation error: first\nParsing error: second"
+        );
+    }
 }
@@ -6,7 +6,9 @@
 //! ## Modules
 //!
 //! - [`compose`](src/compose.rs): Contains utilities for composing options.
+//! - [`derive`](src/derive.rs): Trait to parse options into a struct, derivable with the `derive` feature.
 //! - [`error`](src/error.rs): Defines error types used throughout the library.
+//! - [`matches`](src/matches.rs): Holds the owned values returned by parsing.
 //! - [`options`](src/options.rs): Core module for defining and handling options.
 //!
 //! ## Usage
@@ -24,5 +26,23 @@
 //!
 
 pub mod compose;
+pub mod derive;
 pub mod error;
+pub mod matches;
 pub mod options;
+
+/// Returns the version of the calling crate for [`Compose::version`](compose::Compose::version).
+///
+/// # Examples
+///
+/// ```
+/// use ropts::compose::Compose;
+///
+/// let compose = Compose::new().version(ropts::crate_version!());
+/// ```
+#[macro_export]
+macro_rules! crate_version {
+    () => {
+        env!("CARGO_PKG_VERSION")
+    };
+}
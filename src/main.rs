@@ -19,6 +19,7 @@ fn main() {
         .env("DEMO_NAME")
         .short_arg('n')
         .long_arg("name")
+        .value_name("NAME")
         .additional_eval(|s| {
             if s.len() < 3 {
                 return Err(Error::Validation(
@@ -31,6 +32,7 @@ fn main() {
         .env("DEMO_AGE")
         .short_arg('a')
         .long_arg("age")
+        .value_name("YEARS")
         .additional_eval(|a| {
             if *a < 18 {
                 return Err(Error::Validation(
@@ -47,31 +49,29 @@ fn main() {
     let skills_option = ValuesOption::new(&mut skills, "Your skills")
         .env("DEMO_SKILLS")
         .short_arg('s')
-        .long_arg("skills");
+        .long_arg("skills")
+        .value_name("SKILL");
 
-    // Compose the options and parse the command line arguments
-    let result = Compose::new()
+    // Compose the options and parse the command line arguments,
+    // exiting with a usage error or after printing the help
+    Compose::new()
         .args(args.iter().skip(1).cloned())
         .envs(envs.into_iter())
         .help(|s| println!("{}", s))
+        .version(ropts::crate_version!())
+        .about("Introduces you to the world.")
         .add(name_option)
         .add(age_option)
         .add(employed_option)
         .add(skills_option)
-        .parse();
-
-    // Error handling
-    if let Err(e) = result {
-        eprintln!("{}", e);
-        std::process::exit(1);
-    }
+        .parse_or_exit();
 
     // Use the parsed values
     println!("Hello, {}!", name.unwrap());
     println!("You are {} years old", age.unwrap());
     println!("Are you employed? {}", employed.unwrap());
-    if skills.is_some() {
-        println!("Your skills are: {:?}", skills.unwrap());
+    if let Some(skills) = skills {
+        println!("Your skills are: {:?}", skills);
     } else {
         println!("You have no skills");
     }
@@ -0,0 +1,114 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The parse result of [`Compose::parse_matches`](crate::compose::Compose::parse_matches),
+/// holding the values of all options created with `with_id`.
+///
+/// # Examples
+///
+/// ```
+/// use ropts::compose::Compose;
+/// use ropts::options::{ValueOption, ValuesOption};
+///
+/// let matches = Compose::new()
+///     .args(["--age", "42", "-s", "rust,go"].iter().map(|arg| arg.to_string()))
+///     .add(ValueOption::<u8>::with_id("age", "Your age").long_arg("age"))
+///     .add(ValuesOption::<String>::with_id("skills", "Your skills").short_arg('s'))
+///     .parse_matches()
+///     .unwrap();
+///
+/// assert_eq!(matches.get::<u8>("age"), Some(&42));
+/// assert_eq!(
+///     matches.get_many::<String>("skills"),
+///     Some(&["rust".to_string(), "go".to_string()][..])
+/// );
+/// ```
+#[derive(Default)]
+pub struct Matches {
+    values: HashMap<String, Box<dyn Any>>,
+    subcommand_path: Vec<String>,
+}
+
+impl Matches {
+    pub(crate) fn new(values: HashMap<String, Box<dyn Any>>, subcommand_path: Vec<String>) -> Self {
+        Matches {
+            values,
+            subcommand_path,
+        }
+    }
+
+    /// Returns the value of the option `id`.
+    /// Returns `None` if the option has no value or its value is not of type `T`.
+    pub fn get<T: 'static>(&self, id: &str) -> Option<&T> {
+        self.values.get(id)?.downcast_ref()
+    }
+
+    /// Returns the values of the multi value option `id`, e.g. a `ValuesOption<T>`.
+    /// Returns `None` if the option has no value or its values are not of type `T`.
+    pub fn get_many<T: 'static>(&self, id: &str) -> Option<&[T]> {
+        self.get::<Vec<T>>(id).map(Vec::as_slice)
+    }
+
+    /// Returns `true` if the option `id` has a value.
+    pub fn contains(&self, id: &str) -> bool {
+        self.values.contains_key(id)
+    }
+
+    /// Returns the names of the selected subcommands, e.g. `["db", "migrate"]`.
+    pub fn subcommand_path(&self) -> &[String] {
+        &self.subcommand_path
+    }
+}
+
+impl fmt::Debug for Matches {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ids: Vec<&String> = self.values.keys().collect();
+        ids.sort();
+        f.debug_struct("Matches")
+            .field("ids", &ids)
+            .field("subcommand_path", &self.subcommand_path)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matches;
+    use std::any::Any;
+    use std::collections::HashMap;
+
+    fn matches() -> Matches {
+        let mut values: HashMap<String, Box<dyn Any>> = HashMap::new();
+        values.insert("age".to_string(), Box::new(42u8));
+        values.insert("skills".to_string(), Box::new(vec!["rust".to_string()]));
+        Matches::new(values, vec!["db".to_string()])
+    }
+
+    #[test]
+    fn get() {
+        let matches = matches();
+        assert_eq!(matches.get::<u8>("age"), Some(&42));
+        assert_eq!(matches.get::<u16>("age"), None);
+        assert_eq!(matches.get::<u8>("name"), None);
+    }
+
+    #[test]
+    fn get_many() {
+        let matches = matches();
+        assert_eq!(
+            matches.get_many::<String>("skills"),
+            Some(&["rust".to_string()][..])
+        );
+        assert_eq!(matches.get_many::<u8>("skills"), None);
+        assert_eq!(matches.get_many::<u8>("age"), None);
+    }
+
+    #[test]
+    fn contains_and_path() {
+        let matches = matches();
+        assert!(matches.contains("age"));
+        assert!(!matches.contains("name"));
+        assert_eq!(matches.subcommand_path(), &["db".to_string()]);
+    }
+}
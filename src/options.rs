@@ -1,124 +1,342 @@
+use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+pub mod positional_option;
+pub mod positional_values;
 pub mod utils;
 pub mod value_option;
 pub mod values_option;
-use crate::error::Error;
+use crate::error::{Error, ErrorKind, Identifiers, Source};
+pub use positional_option::PositionalOption;
+pub use positional_values::PositionalValues;
+pub use utils::ValueEnum;
 pub use value_option::ValueOption;
 pub use values_option::ValuesOption;
 
+type EvalFn<'a, T> = Box<dyn Fn(&T) -> Result<(), Error> + 'a>;
+type CompleteFn<'a> = Box<dyn Fn(&str) -> Vec<String> + 'a>;
+
+/// Where an option stores its value, either in a variable of the caller
+/// or in the option itself until it is moved into the parse result.
+enum Slot<'a, T> {
+    Borrowed(&'a mut Option<T>),
+    Owned(Option<T>),
+}
+
+impl<'a, T> From<&'a mut Option<T>> for Slot<'a, T> {
+    fn from(value: &'a mut Option<T>) -> Self {
+        Slot::Borrowed(value)
+    }
+}
+
+impl<T> Deref for Slot<'_, T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Slot::Borrowed(value) => value,
+            Slot::Owned(value) => value,
+        }
+    }
+}
+
+impl<T> DerefMut for Slot<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Slot::Borrowed(value) => value,
+            Slot::Owned(value) => value,
+        }
+    }
+}
+
 pub struct OptionBaseAttributes<'a, T>
 where
     T: Debug + Clone,
 {
+    id: Option<String>,
     description: String,
     env_key: Option<String>,
     long_arg: Option<String>,
     short_arg: Option<char>,
+    positional: Option<(String, PositionalArity)>,
     required: bool,
     default: Option<T>,
-    value: &'a mut Option<T>,
-    additional_eval: Option<Box<dyn Fn(&T) -> Result<(), Error> + 'a>>,
+    value: Slot<'a, T>,
+    additional_eval: Option<EvalFn<'a, T>>,
     type_id: std::any::TypeId,
+    /// Where the current value came from and its raw input.
+    source: Option<(Source, Option<String>)>,
+    /// The help section of the option.
+    group: Option<String>,
+    /// The placeholder of the value in the help, e.g. `INT`.
+    value_name: String,
+    /// The Rust type of a single value, e.g. `u8`.
+    value_type: String,
+    /// Returns the completion candidates of a partial value.
+    completer: Option<CompleteFn<'a>>,
+    /// The accepted values, empty if any value is accepted.
+    choices: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PositionalArity {
+    Single,
+    Variadic,
+}
+
+/// Returns the usage of a positional argument, e.g. `<file>` or `[files...]`.
+pub(crate) fn positional_usage(name: &str, arity: PositionalArity, required: bool) -> String {
+    match (arity, required) {
+        (PositionalArity::Single, true) => format!("<{}>", name),
+        (PositionalArity::Single, false) => format!("[{}]", name),
+        (PositionalArity::Variadic, true) => format!("<{}>...", name),
+        (PositionalArity::Variadic, false) => format!("[{}...]", name),
+    }
+}
+
+/// Describes an option for the help and other generated documentation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OptionInfo {
+    pub description: String,
+    pub env_key: Option<String>,
+    pub short_arg: Option<char>,
+    pub long_arg: Option<String>,
+    /// The name and arity of a positional argument.
+    pub positional: Option<(String, PositionalArity)>,
+    pub required: bool,
+    /// The default value, formatted with `AllowedTypes::display_value`.
+    pub default: Option<String>,
+    pub is_switch: bool,
+    /// The help section, `None` for the default section.
+    pub group: Option<String>,
+    /// The placeholder of the value, `None` for switches and positional arguments.
+    pub value_name: Option<String>,
+    /// The Rust type of a single value, e.g. `u8`.
+    pub value_type: String,
+    /// The accepted values, empty if any value is accepted.
+    pub choices: Vec<String>,
+    /// `true` if the option takes comma separated values.
+    pub multiple: bool,
+    /// `true` if the candidates of the value are returned by [`OptionBase::complete`].
+    pub dynamic_completion: bool,
 }
 
 pub trait OptionBase {
-    fn parse_env(&mut self, envs: &HashMap<String, String>);
-    fn parse_args(&mut self, args: &[String]);
+    fn parse_env(&mut self, envs: &HashMap<String, String>) -> Result<(), Error>;
+    fn parse_arg(&mut self, value: Option<&str>, position: usize) -> Result<(), Error>;
     fn eval(&mut self) -> Result<(), Error>;
-    fn help(&self) -> String;
+    fn info(&self) -> OptionInfo;
+    fn long_arg(&self) -> Option<&str>;
+    fn short_arg(&self) -> Option<char>;
+    fn is_switch(&self) -> bool;
+    fn positional(&self) -> Option<PositionalArity>;
+    fn usage(&self) -> Option<String>;
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)>;
+    /// Returns the completion candidates of a partial value,
+    /// `None` if the option has no completion hook.
+    fn complete(&self, partial: &str) -> Option<Vec<String>>;
+
+    /// Returns a one line summary of the option, e.g. `ENV:NAME ARGS:-n,--name - Your name`.
+    #[deprecated(note = "use `info` instead")]
+    fn help(&self) -> String {
+        let info = self.info();
+        let mut help = String::new();
+
+        if let Some(env_key) = &info.env_key {
+            help.push_str(&format!("ENV:{} ", env_key));
+        }
+        if info.long_arg.is_some() || info.short_arg.is_some() {
+            help.push_str("ARGS:");
+            if let Some(short_arg) = info.short_arg {
+                help.push_str(&format!("-{},", short_arg));
+            }
+            if let Some(long_arg) = &info.long_arg {
+                help.push_str(&format!("--{},", long_arg));
+            }
+            help.pop();
+        }
+        if let Some((name, arity)) = &info.positional {
+            help.push_str(&format!(
+                "ARGS:{}",
+                positional_usage(name, *arity, info.required)
+            ));
+        }
+
+        if info.required {
+            help.push_str("  Required");
+        } else if let Some(default) = &info.default {
+            help.push_str(&format!("  Default: {}\n", default));
+        }
+
+        help.push_str(&format!(" - {}", info.description));
+
+        help
+    }
 }
 
 impl<'a, T> OptionBaseAttributes<'a, T>
 where
     T: Debug + Clone,
 {
-    fn identifier(&self) -> String {
-        let mut identifier = "{".to_string();
-
-        if let Some(env_key) = &self.env_key {
-            identifier.push_str(&format!("{}, ", env_key));
+    fn identifiers(&self) -> Identifiers {
+        Identifiers {
+            env_key: self.env_key.clone(),
+            short_arg: self.short_arg,
+            long_arg: self.long_arg.clone(),
+            positional: self.positional.as_ref().map(|(name, _)| name.clone()),
         }
-        if let Some(short_arg) = self.short_arg {
-            identifier.push_str(&format!("-{}, ", short_arg));
+    }
+
+    /// Returns the usage of a required flag option, e.g. `-n <NAME>`.
+    /// Optional flag options are summarized as `[OPTIONS]` by the compose.
+    fn flag_usage(&self) -> Option<String> {
+        if !self.required {
+            return None;
         }
-        if let Some(long_arg) = &self.long_arg {
-            identifier.push_str(&format!("--{}, ", long_arg));
+        let flag = match (self.short_arg, &self.long_arg) {
+            (Some(short_arg), _) => format!("-{}", short_arg),
+            (None, Some(long_arg)) => format!("--{}", long_arg),
+            (None, None) => return None,
+        };
+        if self.type_id == std::any::TypeId::of::<bool>() {
+            return Some(flag);
         }
+        Some(format!("{} <{}>", flag, self.value_name))
+    }
+
+    fn set_source(&mut self, source: Source, input: Option<&str>) {
+        self.source = Some((source, input.map(String::from)));
+    }
+
+    fn env_error(&self, env_key: &str, input: &str, error: Error) -> Error {
+        Error::new(ErrorKind::InvalidValue)
+            .with_option(self.identifiers())
+            .with_input(input)
+            .with_source(Source::Env(env_key.into()))
+            .with_cause(error)
+    }
+
+    fn arg_error(&self, position: usize, input: &str, error: Error) -> Error {
+        Error::new(ErrorKind::InvalidValue)
+            .with_option(self.identifiers())
+            .with_input(input)
+            .with_source(Source::Arg(position + 1))
+            .with_cause(error)
+    }
 
-        identifier = identifier.trim_end_matches(", ").to_string();
-        identifier.push_str("}");
-        identifier
+    fn missing_value_error(&self, position: usize) -> Error {
+        Error::new(ErrorKind::MissingValue)
+            .with_option(self.identifiers())
+            .with_source(Source::Arg(position + 1))
     }
 }
 
 impl<'a, T> OptionBase for OptionBaseAttributes<'a, T>
 where
-    T: Debug + Clone,
+    T: Debug + Clone + 'static,
 {
-    fn parse_env(&mut self, _: &HashMap<String, String>) {
+    fn parse_env(&mut self, _: &HashMap<String, String>) -> Result<(), Error> {
         panic!("Not implemented, needs to be handled by the option type");
     }
 
-    fn parse_args(&mut self, _: &[String]) {
+    fn parse_arg(&mut self, _: Option<&str>, _: usize) -> Result<(), Error> {
         panic!("Not implemented, needs to be handled by the option type");
     }
 
     fn eval(&mut self) -> Result<(), Error> {
-        if self.env_key.is_none() && self.long_arg.is_none() && self.short_arg.is_none() {
+        if self.env_key.is_none()
+            && self.long_arg.is_none()
+            && self.short_arg.is_none()
+            && self.positional.is_none()
+        {
             return Err(Error::Parsing("No identifier set".into()));
         }
 
         if self.required && self.value.is_none() {
-            return Err(Error::Validation(format!(
-                "{} is required",
-                self.identifier()
-            )));
+            return Err(Error::new(ErrorKind::MissingRequired).with_option(self.identifiers()));
         }
-        if self.value.is_none() {
+        if self.value.is_none() && self.default.is_some() {
             *self.value = self.default.clone();
+            self.source = Some((Source::Default, None));
         }
-        if self.additional_eval.is_some() {
-            let validation_result =
-                self.additional_eval.as_ref().unwrap()(&self.value.as_ref().unwrap());
-            if validation_result.is_err() {
-                return Err(Error::Validation(format!(
-                    "{} failed validation: {}",
-                    self.identifier(),
-                    validation_result.unwrap_err()
-                )));
+        if let (Some(additional_eval), Some(value)) = (&self.additional_eval, self.value.as_ref()) {
+            if let Err(error) = additional_eval(value) {
+                let mut error = Error::new(ErrorKind::ValidationFailed)
+                    .with_option(self.identifiers())
+                    .with_cause(error);
+                if let Some((source, input)) = &self.source {
+                    error = error.with_source(source.clone());
+                    if let Some(input) = input {
+                        error = error.with_input(input.as_str());
+                    }
+                }
+                return Err(error);
             }
         }
 
         Ok(())
     }
 
-    fn help(&self) -> String {
-        let mut help = String::new();
-
-        if let Some(env_key) = &self.env_key {
-            help.push_str(&format!("ENV:{} ", env_key));
-        }
-        if self.long_arg.is_some() || self.short_arg.is_some() {
-            help.push_str(&format!("ARGS:"));
-            if let Some(short_arg) = self.short_arg {
-                help.push_str(&format!("-{},", short_arg));
-            }
-            if let Some(long_arg) = &self.long_arg {
-                help.push_str(&format!("--{},", long_arg));
-            }
-            help.pop();
+    fn info(&self) -> OptionInfo {
+        OptionInfo {
+            description: self.description.clone(),
+            env_key: self.env_key.clone(),
+            short_arg: self.short_arg,
+            long_arg: self.long_arg.clone(),
+            positional: self.positional.clone(),
+            required: self.required,
+            default: self
+                .default
+                .as_ref()
+                .map(|default| format!("{:?}", default)),
+            is_switch: self.is_switch(),
+            group: self.group.clone(),
+            value_name: match self.is_switch() || self.positional.is_some() {
+                true => None,
+                false => Some(self.value_name.clone()),
+            },
+            value_type: self.value_type.clone(),
+            choices: self.choices.clone(),
+            multiple: false,
+            dynamic_completion: self.completer.is_some(),
         }
+    }
 
-        if self.required {
-            help.push_str("  Required");
-        } else if let Some(default) = &self.default {
-            help.push_str(&format!("  Default: {:?}\n", default));
-        }
+    fn long_arg(&self) -> Option<&str> {
+        self.long_arg.as_deref()
+    }
 
-        help.push_str(&format!(" - {}", self.description));
+    fn short_arg(&self) -> Option<char> {
+        self.short_arg
+    }
 
-        help
+    fn is_switch(&self) -> bool {
+        self.type_id == std::any::TypeId::of::<bool>()
+    }
+
+    fn positional(&self) -> Option<PositionalArity> {
+        self.positional.as_ref().map(|(_, arity)| *arity)
+    }
+
+    fn usage(&self) -> Option<String> {
+        let Some((name, arity)) = &self.positional else {
+            return self.flag_usage();
+        };
+        Some(positional_usage(name, *arity, self.required))
+    }
+
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)> {
+        let id = self.id.clone()?;
+        let Slot::Owned(value) = &mut self.value else {
+            return None;
+        };
+        let value: Box<dyn Any> = Box::new(value.take()?);
+        Some((id, value))
+    }
+
+    fn complete(&self, partial: &str) -> Option<Vec<String>> {
+        self.completer.as_ref().map(|completer| completer(partial))
     }
 }
 
@@ -130,134 +348,202 @@ mod tests {
     fn identifier_env() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
-        assert_eq!(opt.identifier(), "{ENV_KEY}");
+        assert_eq!(opt.identifiers().to_string(), "{ENV_KEY}");
     }
 
     #[test]
     fn identifier_short_arg() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "".to_string(),
             env_key: None,
             long_arg: None,
             short_arg: Some('i'),
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
-        assert_eq!(opt.identifier(), "{-i}");
+        assert_eq!(opt.identifiers().to_string(), "{-i}");
     }
 
     #[test]
     fn identifier_long_arg() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "".to_string(),
             env_key: None,
             long_arg: Some("long_arg".to_string()),
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
-        assert_eq!(opt.identifier(), "{--long_arg}");
+        assert_eq!(opt.identifiers().to_string(), "{--long_arg}");
     }
 
     #[test]
     fn identifier_all() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: Some("long_arg".to_string()),
             short_arg: Some('i'),
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
-        assert_eq!(opt.identifier(), "{ENV_KEY, -i, --long_arg}");
+        assert_eq!(opt.identifiers().to_string(), "{ENV_KEY, -i, --long_arg}");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_env() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert_eq!(opt.help(), "ENV:ENV_KEY  - my description");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_short_arg() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: None,
             long_arg: None,
             short_arg: Some('i'),
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert_eq!(opt.help(), "ARGS:-i - my description");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_long_arg() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: None,
             long_arg: Some("long_arg".to_string()),
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert_eq!(opt.help(), "ARGS:--long_arg - my description");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_all() {
         let mut value = None::<String>;
         let opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: Some("long_arg".to_string()),
             short_arg: Some('i'),
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert_eq!(
             opt.help(),
@@ -265,19 +551,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn info() {
+        let mut value = None::<String>;
+        let opt = OptionBaseAttributes::<String> {
+            id: None,
+            description: "my description".to_string(),
+            env_key: Some("ENV_KEY".to_string()),
+            long_arg: Some("long_arg".to_string()),
+            short_arg: Some('i'),
+            positional: None,
+            required: false,
+            default: Some("default".to_string()),
+            value: (&mut value).into(),
+            additional_eval: None,
+            type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
+        };
+        assert_eq!(
+            opt.info(),
+            OptionInfo {
+                description: "my description".to_string(),
+                env_key: Some("ENV_KEY".to_string()),
+                short_arg: Some('i'),
+                long_arg: Some("long_arg".to_string()),
+                positional: None,
+                required: false,
+                default: Some("\"default\"".to_string()),
+                is_switch: false,
+                group: None,
+                value_name: Some("STRING".to_string()),
+                value_type: "String".to_string(),
+                choices: Vec::new(),
+                multiple: false,
+                dynamic_completion: false,
+            }
+        );
+    }
+
     #[test]
     fn eval_no_identifier() {
         let mut value = None::<String>;
         let mut opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: None,
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert!(opt.eval().is_err());
     }
@@ -286,15 +623,23 @@ mod tests {
     fn eval_required() {
         let mut value = None::<String>;
         let mut opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: true,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: None,
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert!(opt.eval().is_err());
     }
@@ -304,15 +649,23 @@ mod tests {
         let mut value = None::<String>;
         {
             let mut opt = OptionBaseAttributes::<String> {
+                id: None,
                 description: "my description".to_string(),
                 env_key: Some("ENV_KEY".to_string()),
                 long_arg: None,
                 short_arg: None,
+                positional: None,
                 required: false,
                 default: Some("default".to_string()),
-                value: &mut value,
+                value: (&mut value).into(),
                 additional_eval: None,
                 type_id: std::any::TypeId::of::<String>(),
+                source: None,
+                group: None,
+                value_name: "STRING".into(),
+                value_type: "String".into(),
+                completer: None,
+                choices: Vec::new(),
             };
             assert!(opt.eval().is_ok());
         }
@@ -323,15 +676,23 @@ mod tests {
     fn eval_additional_eval() {
         let mut value = Some("value".to_string());
         let mut opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: Some(Box::new(|_| Ok(()))),
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert!(opt.eval().is_ok());
     }
@@ -340,15 +701,23 @@ mod tests {
     fn eval_additional_eval_fail() {
         let mut value = Some("value".to_string());
         let mut opt = OptionBaseAttributes::<String> {
+            id: None,
             description: "my description".to_string(),
             env_key: Some("ENV_KEY".to_string()),
             long_arg: None,
             short_arg: None,
+            positional: None,
             required: false,
             default: None,
-            value: &mut value,
+            value: (&mut value).into(),
             additional_eval: Some(Box::new(|_| Err(Error::Validation("fail".into())))),
             type_id: std::any::TypeId::of::<String>(),
+            source: None,
+            group: None,
+            value_name: "STRING".into(),
+            value_type: "String".into(),
+            completer: None,
+            choices: Vec::new(),
         };
         assert!(opt.eval().is_err());
     }
@@ -0,0 +1,255 @@
+use crate::error::{Error, Source};
+use crate::options::utils::{convert, short_type_name, AllowedTypes};
+use crate::options::{OptionBase, OptionBaseAttributes, OptionInfo, PositionalArity, Slot};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+pub struct PositionalOption<'a, T: AllowedTypes> {
+    base: OptionBaseAttributes<'a, T>,
+}
+
+impl<T: AllowedTypes> OptionBase for PositionalOption<'_, T> {
+    fn parse_env(&mut self, envs: &HashMap<String, String>) -> Result<(), Error> {
+        if let Some(env_key) = self.base.env_key.clone() {
+            if let Some(input) = envs.get(&env_key) {
+                let value = convert(input).map_err(|e| self.base.env_error(&env_key, input, e))?;
+                *self.base.value = Some(value);
+                self.base.set_source(Source::Env(env_key), Some(input));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_arg(&mut self, value: Option<&str>, position: usize) -> Result<(), Error> {
+        let input = value.ok_or_else(|| self.base.missing_value_error(position))?;
+        let value = convert(input).map_err(|e| self.base.arg_error(position, input, e))?;
+        *self.base.value = Some(value);
+        self.base.set_source(Source::Arg(position + 1), Some(input));
+
+        Ok(())
+    }
+
+    fn eval(&mut self) -> Result<(), Error> {
+        self.base.eval()?;
+        Ok(())
+    }
+
+    fn info(&self) -> OptionInfo {
+        OptionInfo {
+            default: self.base.default.as_ref().map(T::display_value),
+            ..self.base.info()
+        }
+    }
+
+    fn long_arg(&self) -> Option<&str> {
+        None
+    }
+
+    fn short_arg(&self) -> Option<char> {
+        None
+    }
+
+    fn is_switch(&self) -> bool {
+        false
+    }
+
+    fn positional(&self) -> Option<PositionalArity> {
+        self.base.positional()
+    }
+
+    fn usage(&self) -> Option<String> {
+        self.base.usage()
+    }
+
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)> {
+        self.base.take_match()
+    }
+
+    fn complete(&self, partial: &str) -> Option<Vec<String>> {
+        self.base.complete(partial)
+    }
+}
+
+impl<'a, T> PositionalOption<'a, T>
+where
+    T: AllowedTypes + 'static,
+{
+    pub fn new(value: &'a mut Option<T>, name: &str, description: &str) -> Self {
+        Self::from_slot(None, value.into(), name, description)
+    }
+
+    /// Creates an option that owns its value instead of borrowing a variable.
+    /// The parsed value is returned in the `Matches` of `Compose::parse_matches` under `id`,
+    /// which is also used as the name of the positional argument.
+    pub fn with_id(id: &str, description: &str) -> Self {
+        Self::from_slot(Some(id.into()), Slot::Owned(None), id, description)
+    }
+
+    fn from_slot(id: Option<String>, value: Slot<'a, T>, name: &str, description: &str) -> Self {
+        PositionalOption {
+            base: OptionBaseAttributes {
+                id,
+                description: description.into(),
+                env_key: None,
+                long_arg: None,
+                short_arg: None,
+                positional: Some((name.into(), PositionalArity::Single)),
+                required: false,
+                default: None,
+                value,
+                additional_eval: None,
+                type_id: TypeId::of::<T>(),
+                source: None,
+                group: None,
+                value_name: T::value_name().into(),
+                value_type: short_type_name::<T>(),
+                completer: None,
+                choices: T::choices().into_iter().map(String::from).collect(),
+            },
+        }
+    }
+
+    pub fn env(mut self, key: &str) -> Self {
+        self.base.env_key = Some(key.into());
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.base.required = true;
+        self
+    }
+
+    pub fn default(mut self, value: T) -> Self {
+        self.base.default = Some(value);
+        self
+    }
+
+    pub fn additional_eval(mut self, eval_fkt: impl Fn(&T) -> Result<(), Error> + 'a) -> Self {
+        self.base.additional_eval = Some(Box::new(eval_fkt));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::options::{OptionBase, PositionalArity, PositionalOption};
+
+    #[test]
+    fn parse_env_existing() {
+        let mut value = None::<String>;
+        {
+            let mut opt = PositionalOption::new(&mut value, "file", "test").env("TEST_ENV");
+            let mut env = std::collections::HashMap::new();
+            env.insert("TEST_ENV".to_string(), "test_value".to_string());
+            assert!(opt.parse_env(&env).is_ok());
+        }
+        assert_eq!(value, Some("test_value".to_string()));
+    }
+
+    #[test]
+    fn parse_arg_value() {
+        let mut value = None::<u8>;
+        {
+            let mut opt = PositionalOption::new(&mut value, "count", "test");
+            assert!(opt.parse_arg(Some("42"), 0).is_ok());
+        }
+        assert_eq!(value, Some(42));
+    }
+
+    #[test]
+    fn parse_arg_invalid() {
+        let mut value = None::<u8>;
+        {
+            let mut opt = PositionalOption::new(&mut value, "count", "test");
+            assert_eq!(
+                opt.parse_arg(Some("abc"), 0).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{<count>} failed to parse argument at position 1: \
+                     Error converting from \"abc\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
+        }
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn parse_setters_used() {
+        let mut value = None::<String>;
+        let opt = PositionalOption::new(&mut value, "file", "my description")
+            .env("TEST_ENV")
+            .required()
+            .default("default_value".to_string())
+            .additional_eval(|_| Ok(()));
+
+        assert_eq!(opt.base.description, "my description");
+        assert!(opt.base.additional_eval.is_some());
+        assert_eq!(opt.base.env_key, Some("TEST_ENV".to_string()));
+        assert!(opt.base.required);
+        assert_eq!(opt.base.default, Some("default_value".to_string()));
+        assert_eq!(opt.positional(), Some(PositionalArity::Single));
+        assert_eq!(opt.long_arg(), None);
+        assert_eq!(opt.short_arg(), None);
+    }
+
+    #[test]
+    fn eval_use_default() {
+        let mut value = None::<String>;
+        {
+            let mut opt =
+                PositionalOption::new(&mut value, "file", "test").default("default".to_string());
+            assert!(opt.eval().is_ok());
+        }
+        assert_eq!(value, Some("default".to_string()));
+    }
+
+    #[test]
+    fn eval_required_not_set_and_formatted_error() {
+        let mut value = None::<String>;
+        let mut opt = PositionalOption::new(&mut value, "file", "test").required();
+        assert_eq!(
+            opt.eval().unwrap_err().to_string(),
+            Error::Validation("{<file>} is required".into()).to_string()
+        );
+    }
+
+    #[test]
+    fn eval_validation_failed() {
+        let mut value = Some("test_value".to_string());
+        let mut opt = PositionalOption::new(&mut value, "file", "test")
+            .additional_eval(|_| Err(Error::Validation("validation failed".into())));
+        assert!(opt.eval().is_err());
+    }
+
+    #[test]
+    fn usage() {
+        let mut value = None::<String>;
+        let mut opt = PositionalOption::new(&mut value, "file", "test");
+        assert_eq!(opt.usage(), Some("[file]".to_string()));
+        opt = opt.required();
+        assert_eq!(opt.usage(), Some("<file>".to_string()));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn help() {
+        let mut value = None::<String>;
+        let opt = PositionalOption::new(&mut value, "file", "my description").env("ENV_KEY");
+        assert_eq!(opt.help(), "ENV:ENV_KEY ARGS:[file] - my description");
+    }
+
+    #[test]
+    fn info() {
+        let mut value = None::<String>;
+        let opt = PositionalOption::new(&mut value, "file", "my description").env("ENV_KEY");
+        let info = opt.info();
+        assert_eq!(info.env_key, Some("ENV_KEY".to_string()));
+        assert_eq!(
+            info.positional,
+            Some(("file".to_string(), PositionalArity::Single))
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::{Error, Source};
+use crate::options::utils::{convert, short_type_name, split_convert, AllowedTypes};
+use crate::options::{OptionBase, OptionBaseAttributes, OptionInfo, PositionalArity, Slot};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+pub struct PositionalValues<'a, T: AllowedTypes> {
+    base: OptionBaseAttributes<'a, Vec<T>>,
+}
+
+impl<T: AllowedTypes> OptionBase for PositionalValues<'_, T> {
+    fn parse_env(&mut self, envs: &HashMap<String, String>) -> Result<(), Error> {
+        if let Some(env_key) = self.base.env_key.clone() {
+            if let Some(input) = envs.get(&env_key) {
+                let new_values =
+                    split_convert(input).map_err(|e| self.base.env_error(&env_key, input, e))?;
+                *self.base.value = Some(new_values);
+                self.base.set_source(Source::Env(env_key), Some(input));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_arg(&mut self, value: Option<&str>, position: usize) -> Result<(), Error> {
+        let input = value.ok_or_else(|| self.base.missing_value_error(position))?;
+        let value = convert(input).map_err(|e| self.base.arg_error(position, input, e))?;
+        if let Some(values) = &mut *self.base.value {
+            values.push(value);
+        } else {
+            *self.base.value = Some(vec![value]);
+        }
+        self.base.set_source(Source::Arg(position + 1), Some(input));
+
+        Ok(())
+    }
+
+    fn eval(&mut self) -> Result<(), Error> {
+        self.base.eval()?;
+        Ok(())
+    }
+
+    fn info(&self) -> OptionInfo {
+        OptionInfo {
+            default: self.base.default.as_ref().map(|values| {
+                let values: Vec<String> = values.iter().map(T::display_value).collect();
+                format!("[{}]", values.join(", "))
+            }),
+            ..self.base.info()
+        }
+    }
+
+    fn long_arg(&self) -> Option<&str> {
+        None
+    }
+
+    fn short_arg(&self) -> Option<char> {
+        None
+    }
+
+    fn is_switch(&self) -> bool {
+        false
+    }
+
+    fn positional(&self) -> Option<PositionalArity> {
+        self.base.positional()
+    }
+
+    fn usage(&self) -> Option<String> {
+        self.base.usage()
+    }
+
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)> {
+        self.base.take_match()
+    }
+
+    fn complete(&self, partial: &str) -> Option<Vec<String>> {
+        self.base.complete(partial)
+    }
+}
+
+impl<'a, T: AllowedTypes + 'static> PositionalValues<'a, T> {
+    pub fn new(value: &'a mut Option<Vec<T>>, name: &str, description: &str) -> Self {
+        Self::from_slot(None, value.into(), name, description)
+    }
+
+    /// Creates an option that owns its value instead of borrowing a variable.
+    /// The parsed value is returned in the `Matches` of `Compose::parse_matches` under `id`,
+    /// which is also used as the name of the positional argument.
+    pub fn with_id(id: &str, description: &str) -> Self {
+        Self::from_slot(Some(id.into()), Slot::Owned(None), id, description)
+    }
+
+    fn from_slot(
+        id: Option<String>,
+        value: Slot<'a, Vec<T>>,
+        name: &str,
+        description: &str,
+    ) -> Self {
+        PositionalValues {
+            base: OptionBaseAttributes {
+                id,
+                description: description.into(),
+                env_key: None,
+                long_arg: None,
+                short_arg: None,
+                positional: Some((name.into(), PositionalArity::Variadic)),
+                required: false,
+                default: None,
+                value,
+                additional_eval: None,
+                type_id: TypeId::of::<Vec<T>>(),
+                source: None,
+                group: None,
+                value_name: T::value_name().into(),
+                value_type: short_type_name::<T>(),
+                completer: None,
+                choices: T::choices().into_iter().map(String::from).collect(),
+            },
+        }
+    }
+
+    pub fn env(mut self, key: &str) -> Self {
+        self.base.env_key = Some(key.into());
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.base.required = true;
+        self
+    }
+
+    pub fn default(mut self, value: Vec<T>) -> Self {
+        self.base.default = Some(value);
+        self
+    }
+
+    pub fn additional_eval(mut self, eval_fkt: impl Fn(&Vec<T>) -> Result<(), Error> + 'a) -> Self {
+        self.base.additional_eval = Some(Box::new(eval_fkt));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::options::{OptionBase, PositionalArity, PositionalValues};
+
+    #[test]
+    fn parse_env_existing() {
+        let mut value = None::<Vec<String>>;
+        {
+            let mut opt = PositionalValues::new(&mut value, "files", "test").env("TEST_ENV");
+            let mut env = std::collections::HashMap::new();
+            env.insert("TEST_ENV".to_string(), "a,b".to_string());
+            assert!(opt.parse_env(&env).is_ok());
+        }
+        assert_eq!(value, Some(vec!["a".to_string(), "b".to_string()]));
+    }
+
+    #[test]
+    fn parse_env_empty_element() {
+        let mut value = None::<Vec<u8>>;
+        {
+            let mut opt = PositionalValues::new(&mut value, "counts", "test").env("COUNTS");
+            let mut env = std::collections::HashMap::new();
+            env.insert("COUNTS".to_string(), "1,,2".to_string());
+            assert_eq!(
+                opt.parse_env(&env).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{COUNTS, <counts>} failed to parse environment variable COUNTS: \
+                     Error converting from \"\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
+        }
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn parse_arg_values() {
+        let mut value = None::<Vec<String>>;
+        {
+            let mut opt = PositionalValues::new(&mut value, "files", "test");
+            assert!(opt.parse_arg(Some("a,b"), 0).is_ok());
+            assert!(opt.parse_arg(Some("c"), 1).is_ok());
+        }
+        assert_eq!(value, Some(vec!["a,b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn parse_arg_invalid() {
+        let mut value = None::<Vec<u8>>;
+        {
+            let mut opt = PositionalValues::new(&mut value, "counts", "test");
+            assert!(opt.parse_arg(Some("1"), 0).is_ok());
+            assert_eq!(
+                opt.parse_arg(Some("b"), 1).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{<counts>} failed to parse argument at position 2: \
+                     Error converting from \"b\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
+        }
+        assert_eq!(value, Some(vec![1]));
+    }
+
+    #[test]
+    fn parse_setters_used() {
+        let mut value = None::<Vec<String>>;
+        let opt = PositionalValues::new(&mut value, "files", "my description")
+            .env("TEST_ENV")
+            .required()
+            .default(vec!["default_value".to_string()])
+            .additional_eval(|_| Ok(()));
+
+        assert_eq!(opt.base.description, "my description");
+        assert!(opt.base.additional_eval.is_some());
+        assert_eq!(opt.base.env_key, Some("TEST_ENV".to_string()));
+        assert!(opt.base.required);
+        assert_eq!(opt.base.default, Some(vec!["default_value".to_string()]));
+        assert_eq!(opt.positional(), Some(PositionalArity::Variadic));
+    }
+
+    #[test]
+    fn eval_required_not_set_and_formatted_error() {
+        let mut value = None::<Vec<String>>;
+        let mut opt = PositionalValues::new(&mut value, "files", "test").required();
+        assert_eq!(
+            opt.eval().unwrap_err().to_string(),
+            Error::Validation("{<files>} is required".into()).to_string()
+        );
+    }
+
+    #[test]
+    fn usage() {
+        let mut value = None::<Vec<String>>;
+        let mut opt = PositionalValues::new(&mut value, "files", "test");
+        assert_eq!(opt.usage(), Some("[files...]".to_string()));
+        opt = opt.required();
+        assert_eq!(opt.usage(), Some("<files>...".to_string()));
+    }
+}
@@ -5,104 +5,279 @@ use std::str::FromStr;
 
 pub trait AllowedTypes: fmt::Debug + Clone + FromStr + Any {
     fn as_any(&self) -> &dyn Any;
+
+    /// Returns the placeholder shown for a value of this type in the help, e.g. `INT`.
+    fn value_name() -> &'static str {
+        "VALUE"
+    }
+
+    /// Returns the accepted values of this type, empty if any value is accepted.
+    fn choices() -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Returns the value as shown in the help, e.g. for the default value.
+    fn display_value(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// An enum with a closed set of values, usable in `ValueOption` and `ValuesOption`.
+/// Usually implemented with `#[derive(ValueEnum)]` (requires the `derive` feature),
+/// otherwise `FromStr` can be implemented with [`ValueEnum::from_name`].
+///
+/// # Examples
+///
+/// ```
+/// use ropts::error::Error;
+/// use ropts::options::ValueEnum;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Format {
+///     Json,
+///     Text,
+/// }
+///
+/// impl ValueEnum for Format {
+///     fn variants() -> &'static [Self] {
+///         &[Format::Json, Format::Text]
+///     }
+///
+///     fn name(&self) -> &'static str {
+///         match self {
+///             Format::Json => "json",
+///             Format::Text => "text",
+///         }
+///     }
+/// }
+///
+/// impl std::str::FromStr for Format {
+///     type Err = Error;
+///
+///     fn from_str(name: &str) -> Result<Self, Error> {
+///         Format::from_name(name)
+///     }
+/// }
+///
+/// assert_eq!(Format::from_name("JSON").unwrap(), Format::Json);
+/// ```
+pub trait ValueEnum: Sized + Clone + 'static {
+    /// Returns all variants in the order they are listed in the help.
+    fn variants() -> &'static [Self];
+
+    /// Returns the name of the variant on the command line, e.g. `json`.
+    fn name(&self) -> &'static str;
+
+    /// Returns the variant named `name`, ignoring the case.
+    fn from_name(name: &str) -> Result<Self, Error> {
+        Self::variants()
+            .iter()
+            .find(|variant| variant.name().eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| {
+                invalid_choice(
+                    name,
+                    &Self::variants().iter().map(Self::name).collect::<Vec<_>>(),
+                )
+            })
+    }
+}
+
+impl<T> AllowedTypes for T
+where
+    T: ValueEnum + fmt::Debug + FromStr,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn choices() -> Vec<&'static str> {
+        T::variants().iter().map(T::name).collect()
+    }
+
+    fn display_value(&self) -> String {
+        self.name().to_string()
+    }
 }
 
 impl AllowedTypes for String {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "STRING"
+    }
 }
 
 impl AllowedTypes for i8 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for i16 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for i32 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for i64 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for i128 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for u8 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for u16 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for u32 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for u64 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for u128 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "INT"
+    }
 }
 
 impl AllowedTypes for f32 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "FLOAT"
+    }
 }
 
 impl AllowedTypes for f64 {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "FLOAT"
+    }
 }
 
 impl AllowedTypes for char {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "CHAR"
+    }
 }
 
 impl AllowedTypes for bool {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn value_name() -> &'static str {
+        "BOOL"
+    }
 }
 
 pub fn convert<T: AllowedTypes>(value: &str) -> Result<T, Error> {
-    value.parse().map_err(|_| {
-        Error::Parsing(format!(
+    value.parse().map_err(|_| match T::choices().as_slice() {
+        [] => Error::Parsing(format!(
             "Error converting from {:?} to {}",
             value,
             std::any::type_name::<T>()
-        ))
+        )),
+        choices => invalid_choice(value, choices),
     })
 }
+
+/// Returns the name of `T` without module paths, e.g. `Vec<String>` for `Vec<T>`
+/// instead of `alloc::vec::Vec<alloc::string::String>`.
+pub(crate) fn short_type_name<T>() -> String {
+    std::any::type_name::<T>()
+        .split_inclusive(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .map(|part| part.rsplit("::").next().unwrap_or_default())
+        .collect()
+}
+
+/// Converts a comma separated list, an empty input is an empty list.
+pub fn split_convert<T: AllowedTypes>(value: &str) -> Result<Vec<T>, Error> {
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+    value.split(',').map(convert).collect()
+}
+
+fn invalid_choice(value: &str, choices: &[&str]) -> Error {
+    Error::Parsing(format!("{:?} is not one of {}", value, choices.join(", ")))
+}
@@ -1,7 +1,7 @@
-use crate::error::Error;
-use crate::options::utils::{convert, AllowedTypes};
-use crate::options::{OptionBase, OptionBaseAttributes};
-use std::any::TypeId;
+use crate::error::{Error, Source};
+use crate::options::utils::{convert, short_type_name, AllowedTypes};
+use crate::options::{OptionBase, OptionBaseAttributes, OptionInfo, PositionalArity, Slot};
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 
 pub struct ValueOption<'a, T: AllowedTypes> {
@@ -9,52 +9,37 @@ pub struct ValueOption<'a, T: AllowedTypes> {
 }
 
 impl<T: AllowedTypes> OptionBase for ValueOption<'_, T> {
-    fn parse_env(&mut self, envs: &HashMap<String, String>) {
-        if let Some(env_key) = &self.base.env_key {
-            if let Some(value) = envs.get(env_key) {
-                *self.base.value = Some(convert(value).unwrap());
+    fn parse_env(&mut self, envs: &HashMap<String, String>) -> Result<(), Error> {
+        if let Some(env_key) = self.base.env_key.clone() {
+            if let Some(input) = envs.get(&env_key) {
+                let value = convert(input).map_err(|e| self.base.env_error(&env_key, input, e))?;
+                *self.base.value = Some(value);
+                self.base.set_source(Source::Env(env_key), Some(input));
             }
         }
+
+        Ok(())
     }
 
-    fn parse_args(&mut self, args: &[String]) {
-        if let Some(long) = &self.base.long_arg {
-            let long_flag = format!("--{}", long);
-            if let Some(index) = args.iter().position(|arg| arg == &long_flag) {
-                if let Some(value) = args.get(index + 1) {
-                    *self.base.value = Some(convert(value).unwrap());
-                    return;
-                } else if self.base.type_id == TypeId::of::<bool>() {
-                    if let Some(default) = &self.base.default {
-                        if let Some(default_bool) = default.as_any().downcast_ref::<bool>() {
-                            *self.base.value = Some(convert(&(!default_bool).to_string()).unwrap());
-                        }
-                        return;
+    fn parse_arg(&mut self, input: Option<&str>, position: usize) -> Result<(), Error> {
+        let converted = match input {
+            Some(value) => convert(value).map_err(|e| self.base.arg_error(position, value, e))?,
+            None if self.base.is_switch() => {
+                let mut value = true;
+                if let Some(default) = &self.base.default {
+                    if let Some(default_bool) = default.as_any().downcast_ref::<bool>() {
+                        value = !default_bool;
                     }
-                    *self.base.value = Some(convert("true").unwrap());
-                    return;
                 }
+                let value = value.to_string();
+                convert(&value).map_err(|e| self.base.arg_error(position, &value, e))?
             }
-        }
+            None => return Err(self.base.missing_value_error(position)),
+        };
+        *self.base.value = Some(converted);
+        self.base.set_source(Source::Arg(position + 1), input);
 
-        if let Some(short) = self.base.short_arg {
-            let short_flag = format!("-{}", short);
-            if let Some(index) = args.iter().position(|arg| arg == &short_flag) {
-                if let Some(value) = args.get(index + 1) {
-                    *self.base.value = Some(convert(value).unwrap());
-                    return;
-                } else if self.base.type_id == TypeId::of::<bool>() {
-                    if let Some(default) = &self.base.default {
-                        if let Some(default_bool) = default.as_any().downcast_ref::<bool>() {
-                            *self.base.value = Some(convert(&(!default_bool).to_string()).unwrap());
-                        }
-                        return;
-                    }
-                    *self.base.value = Some(convert("true").unwrap());
-                    return;
-                }
-            }
-        }
+        Ok(())
     }
 
     fn eval(&mut self) -> Result<(), Error> {
@@ -62,8 +47,39 @@ impl<T: AllowedTypes> OptionBase for ValueOption<'_, T> {
         Ok(())
     }
 
-    fn help(&self) -> String {
-        self.base.help()
+    fn info(&self) -> OptionInfo {
+        OptionInfo {
+            default: self.base.default.as_ref().map(T::display_value),
+            ..self.base.info()
+        }
+    }
+
+    fn long_arg(&self) -> Option<&str> {
+        self.base.long_arg()
+    }
+
+    fn short_arg(&self) -> Option<char> {
+        self.base.short_arg()
+    }
+
+    fn is_switch(&self) -> bool {
+        self.base.is_switch()
+    }
+
+    fn positional(&self) -> Option<PositionalArity> {
+        self.base.positional()
+    }
+
+    fn usage(&self) -> Option<String> {
+        self.base.usage()
+    }
+
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)> {
+        self.base.take_match()
+    }
+
+    fn complete(&self, partial: &str) -> Option<Vec<String>> {
+        self.base.complete(partial)
     }
 }
 
@@ -72,17 +88,35 @@ where
     T: AllowedTypes + 'static,
 {
     pub fn new(value: &'a mut Option<T>, description: &str) -> Self {
+        Self::from_slot(None, value.into(), description)
+    }
+
+    /// Creates an option that owns its value instead of borrowing a variable.
+    /// The parsed value is returned in the `Matches` of `Compose::parse_matches` under `id`.
+    pub fn with_id(id: &str, description: &str) -> Self {
+        Self::from_slot(Some(id.into()), Slot::Owned(None), description)
+    }
+
+    fn from_slot(id: Option<String>, value: Slot<'a, T>, description: &str) -> Self {
         ValueOption {
             base: OptionBaseAttributes {
+                id,
                 description: description.into(),
                 env_key: None,
                 long_arg: None,
                 short_arg: None,
+                positional: None,
                 required: false,
                 default: None,
                 value,
                 additional_eval: None,
                 type_id: TypeId::of::<T>(),
+                source: None,
+                group: None,
+                value_name: T::value_name().into(),
+                value_type: short_type_name::<T>(),
+                completer: None,
+                choices: T::choices().into_iter().map(String::from).collect(),
             },
         }
     }
@@ -102,13 +136,26 @@ where
         self
     }
 
+    /// Sets the placeholder of the value in the help, e.g. `YEARS` for `--age <YEARS>`.
+    /// Defaults to the type of the value, e.g. `INT`.
+    pub fn value_name(mut self, name: &str) -> Self {
+        self.base.value_name = name.into();
+        self
+    }
+
+    /// Lists the option under the section `name` in the help.
+    pub fn group(mut self, name: &str) -> Self {
+        self.base.group = Some(name.into());
+        self
+    }
+
     pub fn required(mut self) -> Self {
         self.base.required = true;
         self
     }
 
     pub fn default(mut self, value: T) -> Self {
-        self.base.default = Some(value.into());
+        self.base.default = Some(value);
         self
     }
 
@@ -116,6 +163,13 @@ where
         self.base.additional_eval = Some(Box::new(eval_fkt));
         self
     }
+
+    /// Completes partial values with the candidates returned by `complete_fn`,
+    /// see [`Compose::generate_completions`](crate::compose::Compose::generate_completions).
+    pub fn complete_with(mut self, complete_fn: impl Fn(&str) -> Vec<String> + 'a) -> Self {
+        self.base.completer = Some(Box::new(complete_fn));
+        self
+    }
 }
 
 #[cfg(test)]
@@ -130,7 +184,7 @@ mod tests {
             let mut opt = ValueOption::new(&mut value, "test").env("TEST_ENV");
             let mut env = std::collections::HashMap::new();
             env.insert("TEST_ENV".to_string(), "test_value".to_string());
-            opt.parse_env(&env);
+            assert!(opt.parse_env(&env).is_ok());
         }
         assert_eq!(value, Some("test_value".to_string()));
     }
@@ -141,47 +195,112 @@ mod tests {
         {
             let mut opt = ValueOption::new(&mut value, "test").env("TEST_ENV");
             let env = std::collections::HashMap::new();
-            opt.parse_env(&env);
+            assert!(opt.parse_env(&env).is_ok());
+        }
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn parse_env_invalid() {
+        let mut value = None::<u8>;
+        {
+            let mut opt = ValueOption::new(&mut value, "test").env("TEST_ENV");
+            let mut env = std::collections::HashMap::new();
+            env.insert("TEST_ENV".to_string(), "abc".to_string());
+            assert_eq!(
+                opt.parse_env(&env).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{TEST_ENV} failed to parse environment variable TEST_ENV: \
+                     Error converting from \"abc\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
         }
         assert_eq!(value, None);
     }
 
     #[test]
-    fn parse_args_long_existing() {
+    fn parse_arg_value() {
         let mut value = None::<String>;
         {
             let mut opt = ValueOption::new(&mut value, "test").long_arg("test");
-            opt.parse_args(&["--test".to_string(), "test_value".to_string()]);
+            assert!(opt.parse_arg(Some("test_value"), 1).is_ok());
         }
         assert_eq!(value, Some("test_value".to_string()));
     }
 
     #[test]
-    fn parse_args_long_missing() {
+    fn parse_arg_empty_value() {
         let mut value = None::<String>;
         {
             let mut opt = ValueOption::new(&mut value, "test").long_arg("test");
-            opt.parse_args(&["--not_test".to_string(), "test_value".to_string()]);
+            assert!(opt.parse_arg(Some(""), 0).is_ok());
         }
-        assert_eq!(value, None);
+        assert_eq!(value, Some("".to_string()));
     }
 
     #[test]
-    fn parse_args_short_existing() {
+    fn parse_arg_missing_value() {
         let mut value = None::<String>;
         {
-            let mut opt = ValueOption::new(&mut value, "test").short_arg('t');
-            opt.parse_args(&["-t".to_string(), "test_value".to_string()]);
+            let mut opt = ValueOption::new(&mut value, "test").long_arg("test");
+            assert_eq!(
+                opt.parse_arg(None, 0).unwrap_err().to_string(),
+                Error::Parsing("{--test} expects a value at position 1".into()).to_string()
+            );
         }
-        assert_eq!(value, Some("test_value".to_string()));
+        assert_eq!(value, None);
     }
 
     #[test]
-    fn parse_args_short_missing() {
-        let mut value = None::<String>;
+    fn parse_arg_switch() {
+        let mut value = None::<bool>;
         {
             let mut opt = ValueOption::new(&mut value, "test").short_arg('t');
-            opt.parse_args(&["-n".to_string(), "test_value".to_string()]);
+            assert!(opt.parse_arg(None, 0).is_ok());
+        }
+        assert_eq!(value, Some(true));
+    }
+
+    #[test]
+    fn parse_arg_switch_inverts_default() {
+        let mut value = None::<bool>;
+        {
+            let mut opt = ValueOption::new(&mut value, "test")
+                .short_arg('t')
+                .default(true);
+            assert!(opt.parse_arg(None, 0).is_ok());
+        }
+        assert_eq!(value, Some(false));
+    }
+
+    #[test]
+    fn parse_arg_switch_value() {
+        let mut value = None::<bool>;
+        {
+            let mut opt = ValueOption::new(&mut value, "test")
+                .long_arg("test")
+                .default(true);
+            assert!(opt.parse_arg(Some("true"), 0).is_ok());
+        }
+        assert_eq!(value, Some(true));
+    }
+
+    #[test]
+    fn parse_arg_invalid() {
+        let mut value = None::<u8>;
+        {
+            let mut opt = ValueOption::new(&mut value, "test").long_arg("age");
+            assert_eq!(
+                opt.parse_arg(Some("abc"), 2).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{--age} failed to parse argument at position 3: \
+                     Error converting from \"abc\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
         }
         assert_eq!(value, None);
     }
@@ -195,7 +314,7 @@ mod tests {
         assert!(opt.base.additional_eval.is_none());
         assert_eq!(opt.base.long_arg, None);
         assert_eq!(opt.base.short_arg, None);
-        assert_eq!(opt.base.required, false);
+        assert!(!opt.base.required);
         assert!(opt.base.value.is_none());
         assert_eq!(opt.base.default, None);
         assert!(opt.base.additional_eval.as_deref().is_none());
@@ -213,6 +332,8 @@ mod tests {
             .env("TEST_ENV")
             .long_arg("test")
             .short_arg('t')
+            .value_name("VALUE")
+            .group("Database")
             .required()
             .default("default_value".to_string())
             .additional_eval(eval);
@@ -221,7 +342,9 @@ mod tests {
         assert!(opt.base.additional_eval.is_some());
         assert_eq!(opt.base.long_arg, Some("test".to_string()));
         assert_eq!(opt.base.short_arg, Some('t'));
-        assert_eq!(opt.base.required, true);
+        assert_eq!(opt.base.value_name, "VALUE");
+        assert_eq!(opt.base.group, Some("Database".to_string()));
+        assert!(opt.base.required);
         assert!(opt.base.value.is_none());
         assert_eq!(opt.base.default, Some("default_value".to_string()));
 
@@ -233,6 +356,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn complete() {
+        let mut value = None::<String>;
+        let opt = ValueOption::new(&mut value, "test").long_arg("test");
+        assert_eq!(opt.complete("a"), None);
+        assert!(!opt.info().dynamic_completion);
+
+        let opt = opt.complete_with(|partial| vec![format!("{}b", partial)]);
+        assert_eq!(opt.complete("a"), Some(vec!["ab".to_string()]));
+        assert!(opt.info().dynamic_completion);
+    }
+
     #[test]
     fn eval_not_set() {
         let mut value = None::<String>;
@@ -310,6 +445,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_env_only() {
         let mut value = None::<String>;
         let opt = ValueOption::new(&mut value, "my description").env("ENV_KEY");
@@ -317,6 +453,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_short_arg_only() {
         let mut value = None::<String>;
         let opt = ValueOption::new(&mut value, "my description").short_arg('i');
@@ -324,6 +461,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_long_arg_only() {
         let mut value = None::<String>;
         let opt = ValueOption::new(&mut value, "my description").long_arg("input");
@@ -331,6 +469,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_all_options() {
         let mut value = None::<String>;
         let opt = ValueOption::new(&mut value, "my description")
@@ -339,4 +478,40 @@ mod tests {
             .long_arg("input");
         assert_eq!(opt.help(), "ENV:ENV_KEY ARGS:-i,--input - my description");
     }
+
+    #[test]
+    fn info() {
+        let mut value = None::<String>;
+        let opt = ValueOption::new(&mut value, "my description")
+            .env("ENV_KEY")
+            .short_arg('i')
+            .long_arg("input");
+        let info = opt.info();
+        assert_eq!(info.description, "my description");
+        assert_eq!(info.env_key, Some("ENV_KEY".to_string()));
+        assert_eq!(info.short_arg, Some('i'));
+        assert_eq!(info.long_arg, Some("input".to_string()));
+        assert_eq!(info.positional, None);
+        assert!(!info.required);
+        assert!(!info.is_switch);
+    }
+
+    #[test]
+    fn usage() {
+        let mut value = None::<String>;
+        let mut opt = ValueOption::new(&mut value, "test")
+            .short_arg('n')
+            .long_arg("full-name");
+        assert_eq!(opt.usage(), None);
+        opt = opt.required();
+        assert_eq!(opt.usage(), Some("-n <STRING>".to_string()));
+        opt = opt.value_name("NAME");
+        assert_eq!(opt.usage(), Some("-n <NAME>".to_string()));
+
+        let mut value = None::<bool>;
+        let opt = ValueOption::new(&mut value, "test")
+            .long_arg("force")
+            .required();
+        assert_eq!(opt.usage(), Some("--force".to_string()));
+    }
 }
@@ -1,7 +1,7 @@
-use crate::error::Error;
-use crate::options::utils::{convert, AllowedTypes};
-use crate::options::{OptionBase, OptionBaseAttributes};
-use std::any::TypeId;
+use crate::error::{Error, Source};
+use crate::options::utils::{short_type_name, split_convert, AllowedTypes};
+use crate::options::{OptionBase, OptionBaseAttributes, OptionInfo, PositionalArity, Slot};
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 
 pub struct ValuesOption<'a, T: AllowedTypes> {
@@ -9,64 +9,31 @@ pub struct ValuesOption<'a, T: AllowedTypes> {
 }
 
 impl<T: AllowedTypes> OptionBase for ValuesOption<'_, T> {
-    fn parse_env(&mut self, envs: &HashMap<String, String>) {
-        if let Some(env_key) = &self.base.env_key {
-            if let Some(value) = envs.get(env_key) {
-                let partial = value.split(",");
-                let mut new_values = Vec::new();
-                for p in partial {
-                    new_values.push(convert(p).unwrap());
-                }
+    fn parse_env(&mut self, envs: &HashMap<String, String>) -> Result<(), Error> {
+        if let Some(env_key) = self.base.env_key.clone() {
+            if let Some(input) = envs.get(&env_key) {
+                let new_values =
+                    split_convert(input).map_err(|e| self.base.env_error(&env_key, input, e))?;
                 *self.base.value = Some(new_values);
+                self.base.set_source(Source::Env(env_key), Some(input));
             }
         }
-    }
-
-    fn parse_args(&mut self, args: &[String]) {
-        let mut skip_next = false;
 
-        for (i, arg) in args.iter().enumerate() {
-            if skip_next {
-                // If the previous argument was a flag, skip the current one (it's the value).
-                skip_next = false;
-                continue;
-            }
-
-            // Handle long argument (e.g., --name)
-            if let Some(long) = &self.base.long_arg {
-                let long_flag = format!("--{}", long);
-                if arg == &long_flag {
-                    if let Some(value) = args.get(i + 1) {
-                        let new_values: Vec<_> =
-                            value.split(',').map(|p| convert(p).unwrap()).collect();
-                        if let Some(values) = &mut *self.base.value {
-                            values.extend(new_values);
-                        } else {
-                            *self.base.value = Some(new_values);
-                        }
-                    }
-                    skip_next = true; // Skip the next argument because it's already processed as the value.
-                    continue;
-                }
-            }
+        Ok(())
+    }
 
-            // Handle short argument (e.g., -s)
-            if let Some(short) = self.base.short_arg {
-                let short_flag = format!("-{}", short);
-                if arg == &short_flag {
-                    if let Some(value) = args.get(i + 1) {
-                        let new_values: Vec<_> =
-                            value.split(',').map(|p| convert(p).unwrap()).collect();
-                        if let Some(values) = &mut *self.base.value {
-                            values.extend(new_values);
-                        } else {
-                            *self.base.value = Some(new_values);
-                        }
-                    }
-                    skip_next = true; // Skip the next argument because it's already processed as the value.
-                }
-            }
+    fn parse_arg(&mut self, value: Option<&str>, position: usize) -> Result<(), Error> {
+        let value = value.ok_or_else(|| self.base.missing_value_error(position))?;
+        let new_values =
+            split_convert(value).map_err(|e| self.base.arg_error(position, value, e))?;
+        if let Some(values) = &mut *self.base.value {
+            values.extend(new_values);
+        } else {
+            *self.base.value = Some(new_values);
         }
+        self.base.set_source(Source::Arg(position + 1), Some(value));
+
+        Ok(())
     }
 
     fn eval(&mut self) -> Result<(), Error> {
@@ -74,24 +41,77 @@ impl<T: AllowedTypes> OptionBase for ValuesOption<'_, T> {
         Ok(())
     }
 
-    fn help(&self) -> String {
-        self.base.help()
+    fn info(&self) -> OptionInfo {
+        OptionInfo {
+            multiple: true,
+            default: self.base.default.as_ref().map(|values| {
+                let values: Vec<String> = values.iter().map(T::display_value).collect();
+                format!("[{}]", values.join(", "))
+            }),
+            ..self.base.info()
+        }
+    }
+
+    fn long_arg(&self) -> Option<&str> {
+        self.base.long_arg()
+    }
+
+    fn short_arg(&self) -> Option<char> {
+        self.base.short_arg()
+    }
+
+    fn is_switch(&self) -> bool {
+        self.base.is_switch()
+    }
+
+    fn positional(&self) -> Option<PositionalArity> {
+        self.base.positional()
+    }
+
+    fn usage(&self) -> Option<String> {
+        self.base.usage()
+    }
+
+    fn take_match(&mut self) -> Option<(String, Box<dyn Any>)> {
+        self.base.take_match()
+    }
+
+    fn complete(&self, partial: &str) -> Option<Vec<String>> {
+        self.base.complete(partial)
     }
 }
 
 impl<'a, T: AllowedTypes + 'static> ValuesOption<'a, T> {
     pub fn new(value: &'a mut Option<Vec<T>>, description: &str) -> Self {
+        Self::from_slot(None, value.into(), description)
+    }
+
+    /// Creates an option that owns its value instead of borrowing a variable.
+    /// The parsed value is returned in the `Matches` of `Compose::parse_matches` under `id`.
+    pub fn with_id(id: &str, description: &str) -> Self {
+        Self::from_slot(Some(id.into()), Slot::Owned(None), description)
+    }
+
+    fn from_slot(id: Option<String>, value: Slot<'a, Vec<T>>, description: &str) -> Self {
         ValuesOption {
             base: OptionBaseAttributes {
+                id,
                 description: description.into(),
                 env_key: None,
                 long_arg: None,
                 short_arg: None,
+                positional: None,
                 required: false,
                 default: None,
                 value,
                 additional_eval: None,
                 type_id: TypeId::of::<Vec<T>>(),
+                source: None,
+                group: None,
+                value_name: T::value_name().into(),
+                value_type: short_type_name::<T>(),
+                completer: None,
+                choices: T::choices().into_iter().map(String::from).collect(),
             },
         }
     }
@@ -111,13 +131,26 @@ impl<'a, T: AllowedTypes + 'static> ValuesOption<'a, T> {
         self
     }
 
+    /// Sets the placeholder of the value in the help, e.g. `YEARS` for `--age <YEARS>`.
+    /// Defaults to the type of the value, e.g. `INT`.
+    pub fn value_name(mut self, name: &str) -> Self {
+        self.base.value_name = name.into();
+        self
+    }
+
+    /// Lists the option under the section `name` in the help.
+    pub fn group(mut self, name: &str) -> Self {
+        self.base.group = Some(name.into());
+        self
+    }
+
     pub fn required(mut self) -> Self {
         self.base.required = true;
         self
     }
 
     pub fn default(mut self, value: Vec<T>) -> Self {
-        self.base.default = Some(value.into());
+        self.base.default = Some(value);
         self
     }
 
@@ -125,6 +158,13 @@ impl<'a, T: AllowedTypes + 'static> ValuesOption<'a, T> {
         self.base.additional_eval = Some(Box::new(eval_fkt));
         self
     }
+
+    /// Completes partial values with the candidates returned by `complete_fn`,
+    /// see [`Compose::generate_completions`](crate::compose::Compose::generate_completions).
+    pub fn complete_with(mut self, complete_fn: impl Fn(&str) -> Vec<String> + 'a) -> Self {
+        self.base.completer = Some(Box::new(complete_fn));
+        self
+    }
 }
 
 #[cfg(test)]
@@ -139,7 +179,7 @@ mod tests {
             let mut opt = ValuesOption::new(&mut value, "test").env("TEST_ENV");
             let mut env = std::collections::HashMap::new();
             env.insert("TEST_ENV".to_string(), "a".to_string());
-            opt.parse_env(&env);
+            assert!(opt.parse_env(&env).is_ok());
         }
         assert_eq!(value, Some(vec!["a".to_string()]));
     }
@@ -151,7 +191,7 @@ mod tests {
             let mut opt = ValuesOption::new(&mut value, "test").env("TEST_ENV");
             let mut env = std::collections::HashMap::new();
             env.insert("TEST_ENV".to_string(), "a,b,c".to_string());
-            opt.parse_env(&env);
+            assert!(opt.parse_env(&env).is_ok());
         }
         assert_eq!(
             value,
@@ -159,33 +199,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_env_invalid() {
+        let mut value = None::<Vec<u8>>;
+        {
+            let mut opt = ValuesOption::new(&mut value, "test").env("TEST_ENV");
+            let mut env = std::collections::HashMap::new();
+            env.insert("TEST_ENV".to_string(), "1,b,3".to_string());
+            assert!(opt.parse_env(&env).is_err());
+        }
+        assert_eq!(value, None);
+    }
+
     #[test]
     fn parse_env_missing() {
         let mut value = None::<Vec<String>>;
         {
             let mut opt = ValuesOption::new(&mut value, "test").env("TEST_ENV");
             let env = std::collections::HashMap::new();
-            opt.parse_env(&env);
+            assert!(opt.parse_env(&env).is_ok());
         }
         assert_eq!(value, None);
     }
 
     #[test]
-    fn parse_single_args_long_existing() {
+    fn parse_single_arg() {
         let mut value = None::<Vec<String>>;
         {
             let mut opt = ValuesOption::new(&mut value, "test").long_arg("test");
-            opt.parse_args(&["--test".to_string(), "a".to_string()]);
+            assert!(opt.parse_arg(Some("a"), 1).is_ok());
         }
         assert_eq!(value, Some(vec!["a".to_string()]));
     }
 
     #[test]
-    fn parse_multiple_args_long_existing() {
+    fn parse_multiple_arg() {
         let mut value = None::<Vec<String>>;
         {
             let mut opt = ValuesOption::new(&mut value, "test").long_arg("test");
-            opt.parse_args(&["--test".to_string(), "a,b,c".to_string()]);
+            assert!(opt.parse_arg(Some("a,b,c"), 1).is_ok());
         }
         assert_eq!(
             value,
@@ -194,44 +246,53 @@ mod tests {
     }
 
     #[test]
-    fn parse_args_long_missing() {
+    fn parse_arg_extends() {
         let mut value = None::<Vec<String>>;
         {
-            let mut opt = ValuesOption::new(&mut value, "test").long_arg("test");
-            opt.parse_args(&["--not_test".to_string(), "a".to_string()]);
+            let mut opt = ValuesOption::new(&mut value, "test").short_arg('t');
+            assert!(opt.parse_arg(Some("a,b"), 1).is_ok());
+            assert!(opt.parse_arg(Some("c"), 3).is_ok());
         }
-        assert_eq!(value, None);
+        assert_eq!(
+            value,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
     }
 
     #[test]
-    fn parse_single_args_short_existing() {
+    fn parse_arg_empty_value() {
         let mut value = None::<Vec<String>>;
         {
-            let mut opt = ValuesOption::new(&mut value, "test").short_arg('t');
-            opt.parse_args(&["-t".to_string(), "a".to_string()]);
+            let mut opt = ValuesOption::new(&mut value, "test").long_arg("test");
+            assert!(opt.parse_arg(Some(""), 0).is_ok());
         }
-        assert_eq!(value, Some(vec!["a".to_string()]));
+        assert_eq!(value, Some(vec![]));
     }
 
     #[test]
-    fn parse_multiple_args_short_existing() {
+    fn parse_arg_missing_value() {
         let mut value = None::<Vec<String>>;
         {
             let mut opt = ValuesOption::new(&mut value, "test").short_arg('t');
-            opt.parse_args(&["-t".to_string(), "a,b,c".to_string()]);
+            assert!(opt.parse_arg(None, 0).is_err());
         }
-        assert_eq!(
-            value,
-            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
-        );
+        assert_eq!(value, None);
     }
 
     #[test]
-    fn parse_args_short_missing() {
-        let mut value = None::<Vec<String>>;
+    fn parse_arg_invalid() {
+        let mut value = None::<Vec<u8>>;
         {
             let mut opt = ValuesOption::new(&mut value, "test").short_arg('t');
-            opt.parse_args(&["-n".to_string(), "a".to_string()]);
+            assert_eq!(
+                opt.parse_arg(Some("1,b"), 1).unwrap_err().to_string(),
+                Error::Parsing(
+                    "{-t} failed to parse argument at position 2: \
+                     Error converting from \"b\" to u8"
+                        .into()
+                )
+                .to_string()
+            );
         }
         assert_eq!(value, None);
     }
@@ -245,7 +306,7 @@ mod tests {
         assert!(opt.base.additional_eval.is_none());
         assert_eq!(opt.base.long_arg, None);
         assert_eq!(opt.base.short_arg, None);
-        assert_eq!(opt.base.required, false);
+        assert!(!opt.base.required);
         assert!(opt.base.value.is_none());
         assert_eq!(opt.base.default, None);
         assert!(opt.base.additional_eval.as_deref().is_none());
@@ -263,6 +324,8 @@ mod tests {
             .env("TEST_ENV")
             .long_arg("test")
             .short_arg('t')
+            .value_name("VALUE")
+            .group("Database")
             .required()
             .default(vec!["default_value".to_string()])
             .additional_eval(eval);
@@ -271,7 +334,9 @@ mod tests {
         assert!(opt.base.additional_eval.is_some());
         assert_eq!(opt.base.long_arg, Some("test".to_string()));
         assert_eq!(opt.base.short_arg, Some('t'));
-        assert_eq!(opt.base.required, true);
+        assert_eq!(opt.base.value_name, "VALUE");
+        assert_eq!(opt.base.group, Some("Database".to_string()));
+        assert!(opt.base.required);
         assert!(opt.base.value.is_none());
         assert_eq!(opt.base.default, Some(vec!["default_value".to_string()]));
 
@@ -360,6 +425,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_env_only() {
         let mut value = None::<Vec<String>>;
         let opt = ValuesOption::new(&mut value, "my description").env("ENV_KEY");
@@ -367,6 +433,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_short_arg_only() {
         let mut value = None::<Vec<String>>;
         let opt = ValuesOption::new(&mut value, "my description").short_arg('i');
@@ -374,6 +441,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_no_long_arg_only() {
         let mut value = None::<Vec<String>>;
         let opt = ValuesOption::new(&mut value, "my description").long_arg("input");
@@ -381,6 +449,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn help_all_options() {
         let mut value = None::<Vec<String>>;
         let opt = ValuesOption::new(&mut value, "my description")
@@ -389,4 +458,21 @@ mod tests {
             .long_arg("input");
         assert_eq!(opt.help(), "ENV:ENV_KEY ARGS:-i,--input - my description");
     }
+
+    #[test]
+    fn info() {
+        let mut value = None::<Vec<String>>;
+        let opt = ValuesOption::new(&mut value, "my description")
+            .env("ENV_KEY")
+            .short_arg('i')
+            .long_arg("input");
+        let info = opt.info();
+        assert_eq!(info.description, "my description");
+        assert_eq!(info.env_key, Some("ENV_KEY".to_string()));
+        assert_eq!(info.short_arg, Some('i'));
+        assert_eq!(info.long_arg, Some("input".to_string()));
+        assert_eq!(info.positional, None);
+        assert!(!info.required);
+        assert!(!info.is_switch);
+    }
 }